@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+use crate::atlas::Atlas;
+use crate::error::Error;
+use crate::theme::Theme;
+
+pub const EMBEDDED: &str = "<embedded default theme>";
+
+const DEFAULT_THEME: &str = include_str!("../assets/themes/default/theme.toml");
+const DEFAULT_ATLAS: &str = include_str!("../assets/themes/default/atlas.toml");
+const DEFAULT_TEXTURE: &[u8] = include_bytes!("../assets/themes/default/tile.png");
+
+/// `$XDG_DATA_HOME/car-demo`, where saved times and the ghost live.
+pub fn data_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("share"))
+        })
+        .map(|directory| directory.join("car-demo"))
+}
+
+fn data_dirs() -> Vec<PathBuf> {
+    let system = std::env::var_os("XDG_DATA_DIRS")
+        .filter(|paths| !paths.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+
+    data_home()
+        .into_iter()
+        .chain(std::env::split_paths(&system).map(|directory| directory.join("car-demo")))
+        .collect()
+}
+
+pub struct Assets {
+    roots: Vec<PathBuf>,
+}
+
+impl Assets {
+    pub fn new(directory: Option<&Path>) -> Self {
+        let beside_executable = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.parent().map(|directory| directory.join("assets")));
+
+        let roots: Vec<PathBuf> = directory
+            .map(Path::to_path_buf)
+            .into_iter()
+            .chain(beside_executable)
+            .chain(data_dirs())
+            .chain([PathBuf::from("assets")])
+            .collect();
+        log::debug!("looking for assets in {roots:?}");
+
+        Self { roots }
+    }
+
+    fn candidates(&self, path: &Path) -> Vec<PathBuf> {
+        if path.is_absolute() {
+            return vec![path.to_path_buf()];
+        }
+
+        let relative = path.strip_prefix("assets").unwrap_or(path);
+        self.roots.iter().map(|root| root.join(relative)).collect()
+    }
+
+    fn theme_candidates(&self, directory: &Path) -> Vec<PathBuf> {
+        let mut candidates = self.candidates(directory);
+        if !directory.is_absolute() {
+            candidates.extend(self.candidates(&Path::new("themes").join(directory)));
+        }
+        candidates
+    }
+
+    fn find_theme(&self, directory: &Path) -> Option<PathBuf> {
+        self.theme_candidates(directory)
+            .into_iter()
+            .find(|candidate| candidate.join("theme.toml").is_file())
+    }
+
+    pub fn theme(&self, directory: &Path, requested: bool) -> Result<PathBuf, Error> {
+        if let Some(found) = self.find_theme(directory) {
+            return Ok(found);
+        }
+        if !requested {
+            return Ok(directory.to_path_buf());
+        }
+
+        let searched: Vec<String> = self
+            .theme_candidates(directory)
+            .iter()
+            .map(|candidate| candidate.display().to_string())
+            .collect();
+        Err(Error::asset(
+            directory,
+            format!("theme not found, searched {}", searched.join(", ")),
+        ))
+    }
+
+    pub fn theme_name(&self, directory: &Path) -> PathBuf {
+        directory
+            .file_name()
+            .map(PathBuf::from)
+            .filter(|name| self.find_theme(name).as_deref() == Some(directory))
+            .unwrap_or_else(|| directory.to_path_buf())
+    }
+}
+
+pub fn load_theme(directory: &Path) -> Result<(Theme, Atlas), Error> {
+    if directory.join("theme.toml").is_file() {
+        return Ok((
+            Theme::load(directory)?,
+            Atlas::load(&directory.join("atlas.toml"))?,
+        ));
+    }
+
+    log::warn!(
+        "theme {} not found, using the embedded default theme",
+        directory.display()
+    );
+    Ok((
+        Theme::parse(DEFAULT_THEME, Path::new(EMBEDDED))?,
+        Atlas::embedded(DEFAULT_ATLAS, DEFAULT_TEXTURE)?,
+    ))
+}
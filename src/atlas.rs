@@ -0,0 +1,295 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use sdl2::image::LoadTexture;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget, Texture, TextureCreator};
+use serde::Deserialize;
+
+use crate::assets::EMBEDDED;
+use crate::error::Error;
+
+const REQUIRED_SPRITES: [&str; 15] = [
+    "tachometer",
+    "gear_knob",
+    "shifter_base",
+    "hand_open",
+    "hand_grabbing",
+    "pedal_up",
+    "pedal_down",
+    "gear_neutral",
+    "gear_rocket",
+    "gear_first",
+    "gear_second",
+    "gear_third",
+    "gear_fourth",
+    "gear_fifth",
+    "speed_label",
+];
+
+#[derive(Deserialize)]
+struct Region {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct Size {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct FontManifest {
+    x: i32,
+    y: i32,
+    columns: u32,
+    cell: Size,
+    glyph: Size,
+    characters: String,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    texture: PathBuf,
+    sprites: HashMap<String, Region>,
+    font: FontManifest,
+}
+
+pub struct Font {
+    glyphs: HashMap<char, Rect>,
+    pub advance: u32,
+    pub size: (u32, u32),
+}
+
+impl Font {
+    fn from_manifest(manifest: FontManifest) -> Result<Self, String> {
+        if manifest.columns == 0 {
+            return Err("font needs at least one column".to_string());
+        }
+
+        let mut glyphs = HashMap::new();
+        for (index, character) in (0..).zip(manifest.characters.chars()) {
+            let column = i32::try_from(index % manifest.columns).map_err(|e| e.to_string())?;
+            let row = i32::try_from(index / manifest.columns).map_err(|e| e.to_string())?;
+            let cell_w = i32::try_from(manifest.cell.w).map_err(|e| e.to_string())?;
+            let cell_h = i32::try_from(manifest.cell.h).map_err(|e| e.to_string())?;
+            let rect = Rect::new(
+                manifest.x + column * cell_w,
+                manifest.y + row * cell_h,
+                manifest.glyph.w,
+                manifest.glyph.h,
+            );
+            glyphs.insert(character, rect);
+        }
+
+        if !glyphs.contains_key(&'?') {
+            return Err("font is missing the `?` fallback glyph".to_string());
+        }
+
+        Ok(Self {
+            glyphs,
+            advance: manifest.cell.w,
+            size: (manifest.glyph.w, manifest.glyph.h),
+        })
+    }
+
+    pub fn glyph(&self, character: char) -> Option<Rect> {
+        if character == ' ' {
+            return None;
+        }
+
+        self.glyphs
+            .get(&character)
+            .or_else(|| self.glyphs.get(&character.to_ascii_uppercase()))
+            .or_else(|| self.glyphs.get(&'?'))
+            .copied()
+    }
+}
+
+pub enum Image {
+    File(PathBuf),
+    Embedded(&'static [u8]),
+}
+
+impl Image {
+    pub fn path(&self) -> &Path {
+        match self {
+            Image::File(path) => path,
+            Image::Embedded(_) => Path::new(EMBEDDED),
+        }
+    }
+}
+
+pub struct Atlas {
+    pub texture: Image,
+    pub font: Font,
+    sprites: HashMap<String, Rect>,
+}
+
+impl Atlas {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::asset(path, format!("unable to read atlas manifest: {e}")))?;
+        let directory = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::parse(&content, path, |texture| {
+            Image::File(directory.join(texture))
+        })
+    }
+
+    pub fn embedded(content: &str, texture: &'static [u8]) -> Result<Self, Error> {
+        Self::parse(content, Path::new(EMBEDDED), |_| Image::Embedded(texture))
+    }
+
+    fn parse(
+        content: &str,
+        path: &Path,
+        image: impl FnOnce(PathBuf) -> Image,
+    ) -> Result<Self, Error> {
+        let manifest: Manifest = toml::from_str(content)
+            .map_err(|e| Error::asset(path, format!("invalid atlas manifest: {e}")))?;
+
+        let missing: Vec<&str> = REQUIRED_SPRITES
+            .into_iter()
+            .filter(|name| !manifest.sprites.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(Error::asset(
+                path,
+                format!("atlas manifest is missing sprites: {}", missing.join(", ")),
+            ));
+        }
+
+        let sprites = manifest
+            .sprites
+            .into_iter()
+            .map(|(name, Region { x, y, w, h })| (name, Rect::new(x, y, w, h)))
+            .collect();
+
+        let font = Font::from_manifest(manifest.font)
+            .map_err(|e| Error::asset(path, format!("invalid font in atlas manifest: {e}")))?;
+
+        Ok(Self {
+            texture: image(manifest.texture),
+            font,
+            sprites,
+        })
+    }
+
+    pub fn sprite(&self, name: &str) -> Result<Rect, Error> {
+        self.sprites.get(name).copied().ok_or_else(|| {
+            Error::asset(self.texture.path(), format!("sprite `{name}` not in atlas"))
+        })
+    }
+
+    fn check_bounds(&self, (width, height): (u32, u32)) -> Result<(), Error> {
+        let bounds = Rect::new(0, 0, width, height);
+        for (name, rect) in &self.sprites {
+            if !bounds.contains_rect(*rect) {
+                return Err(Error::asset(
+                    self.texture.path(),
+                    format!("sprite `{name}` {rect:?} is outside of the {width}x{height} texture"),
+                ));
+            }
+        }
+        for (character, rect) in &self.font.glyphs {
+            if !bounds.contains_rect(*rect) {
+                return Err(Error::asset(
+                    self.texture.path(),
+                    format!(
+                        "glyph `{character}` {rect:?} is outside of the {width}x{height} texture"
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Sprites<'a> {
+    texture: RefCell<Texture<'a>>,
+    pub atlas: Atlas,
+    #[cfg(feature = "ttf")]
+    true_type: Option<TrueType<'a>>,
+}
+
+#[cfg(feature = "ttf")]
+pub struct TrueType<'a> {
+    pub path: PathBuf,
+    pub font: sdl2::ttf::Font<'a, 'static>,
+    pub texture_creator: &'a TextureCreator<sdl2::video::WindowContext>,
+}
+
+impl<'a> Sprites<'a> {
+    pub fn load<C>(texture_creator: &'a TextureCreator<C>, atlas: Atlas) -> Result<Self, Error> {
+        let texture = match &atlas.texture {
+            Image::File(path) => texture_creator.load_texture(path),
+            Image::Embedded(bytes) => texture_creator.load_texture_bytes(bytes),
+        }
+        .map_err(|e| Error::asset(atlas.texture.path(), format!("unable to load texture: {e}")))?;
+        let query = texture.query();
+        atlas.check_bounds((query.width, query.height))?;
+
+        Ok(Self {
+            texture: RefCell::new(texture),
+            atlas,
+            #[cfg(feature = "ttf")]
+            true_type: None,
+        })
+    }
+
+    #[cfg(feature = "ttf")]
+    pub fn with_true_type(self, true_type: TrueType<'a>) -> Self {
+        Self {
+            true_type: Some(true_type),
+            ..self
+        }
+    }
+
+    #[cfg(feature = "ttf")]
+    pub fn true_type(&self) -> Option<&TrueType<'a>> {
+        self.true_type.as_ref()
+    }
+
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        name: &str,
+        target: Rect,
+    ) -> Result<(), Error> {
+        canvas
+            .copy(&self.texture.borrow(), self.atlas.sprite(name)?, target)
+            .map_err(Error::video)
+    }
+
+    pub fn draw_faded<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        name: &str,
+        target: Rect,
+        alpha: u8,
+    ) -> Result<(), Error> {
+        let source = self.atlas.sprite(name)?;
+        self.draw_tinted(canvas, source, target, Color::RGBA(255, 255, 255, alpha))
+    }
+
+    pub fn draw_tinted<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        source: Rect,
+        target: Rect,
+        color: Color,
+    ) -> Result<(), Error> {
+        let mut texture = self.texture.borrow_mut();
+        texture.set_color_mod(color.r, color.g, color.b);
+        texture.set_alpha_mod(color.a);
+        let result = canvas.copy(&texture, source, target);
+        texture.set_color_mod(255, 255, 255);
+        texture.set_alpha_mod(255);
+        result.map_err(Error::video)
+    }
+}
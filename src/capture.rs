@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use sdl2::image::SaveSurface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{Canvas, RenderTarget};
+use sdl2::surface::Surface;
+
+use crate::error::Error;
+
+pub fn save_png<T: RenderTarget>(canvas: &Canvas<T>, path: &Path) -> Result<(), Error> {
+    let (width, height) = canvas.output_size().map_err(Error::video)?;
+    let mut pixels = canvas
+        .read_pixels(None, PixelFormatEnum::RGB24)
+        .map_err(Error::video)?;
+    let surface = Surface::from_data(
+        &mut pixels,
+        width,
+        height,
+        width * 3,
+        PixelFormatEnum::RGB24,
+    )
+    .map_err(Error::video)?;
+
+    surface
+        .save(path)
+        .map_err(|e| Error::asset(path, format!("unable to save png: {e}")))
+}
+
+pub fn screenshot<T: RenderTarget>(canvas: &Canvas<T>, directory: &Path) -> Result<PathBuf, Error> {
+    let name = Local::now().format("car-demo-%Y%m%d-%H%M%S%.3f.png");
+    let path = directory.join(name.to_string());
+    save_png(canvas, &path)?;
+    Ok(path)
+}
+
+pub struct FrameCapture {
+    directory: PathBuf,
+    frame: u64,
+}
+
+impl FrameCapture {
+    pub fn new(directory: PathBuf) -> Result<Self, Error> {
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| Error::asset(&directory, format!("unable to create directory: {e}")))?;
+        Ok(Self {
+            directory,
+            frame: 0,
+        })
+    }
+
+    pub fn capture<T: RenderTarget>(&mut self, canvas: &Canvas<T>) -> Result<(), Error> {
+        let path = self.directory.join(format!("frame_{:06}.png", self.frame));
+        save_png(canvas, &path)?;
+        self.frame += 1;
+        Ok(())
+    }
+}
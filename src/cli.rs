@@ -1,15 +1,143 @@
-pub use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use log::LevelFilter;
+use std::path::PathBuf;
+
+use crate::cluster::Cluster;
+use crate::frame::Renderer;
+use crate::scene::Scene;
+use crate::telemetry::{Corner, MAX_SECONDS};
+use crate::timing::Benchmark;
+use crate::units::Units;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Cli {
-    #[arg(short, long, default_value_t = 128.0)]
-    pub mouse_sensitivity: f64,
-
-    #[arg(short, long, default_value_t = false)]
-    pub windowed: bool,
+    #[arg(long, global = true)]
+    pub config: Option<PathBuf>,
 
-    #[arg(short, long, default_value_t = LevelFilter::Info)]
+    #[arg(short, long, global = true, default_value_t = LevelFilter::Info)]
     pub log_level: LevelFilter,
+
+    #[command(flatten)]
+    pub play: PlayArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    Play(PlayArgs),
+    Replay {
+        file: PathBuf,
+
+        #[command(flatten)]
+        play: PlayArgs,
+    },
+    Simulate {
+        #[arg(long = "script", required = true)]
+        scripts: Vec<PathBuf>,
+    },
+    ValidateVehicle {
+        file: PathBuf,
+    },
+    Golden(GoldenArgs),
+}
+
+#[derive(Args)]
+pub struct GoldenArgs {
+    #[arg(long)]
+    pub assets: Option<PathBuf>,
+
+    #[arg(long, default_value = "golden")]
+    pub directory: PathBuf,
+
+    #[arg(long, default_value_t = false)]
+    pub bless: bool,
+}
+
+#[derive(Args, Clone)]
+pub struct PlayArgs {
+    #[arg(short, long)]
+    pub mouse_sensitivity: Option<f64>,
+
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "true")]
+    pub windowed: Option<bool>,
+
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    #[arg(long)]
+    pub scale: Option<f64>,
+
+    #[arg(long, value_enum)]
+    pub renderer: Option<Renderer>,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub vsync: Option<bool>,
+
+    #[arg(long)]
+    pub max_fps: Option<u32>,
+
+    #[arg(long)]
+    pub assets: Option<PathBuf>,
+
+    #[arg(short, long)]
+    pub theme: Option<PathBuf>,
+
+    #[arg(short, long, value_enum)]
+    pub cluster: Option<Cluster>,
+
+    #[arg(short, long, value_enum)]
+    pub units: Option<Units>,
+
+    #[arg(short, long, value_enum)]
+    pub scene: Option<Scene>,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub camera_shake: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub pedal_traces: Option<bool>,
+
+    #[arg(long, num_args = 0..=1, default_missing_value = "true")]
+    pub telemetry: Option<bool>,
+
+    #[arg(long, value_enum)]
+    pub telemetry_corner: Option<Corner>,
+
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=i64::from(MAX_SECONDS)))]
+    pub telemetry_seconds: Option<u32>,
+
+    #[arg(long, default_value_t = false)]
+    pub tutorial: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "tutorial")]
+    pub drag: bool,
+
+    #[arg(long, default_value_t = false, requires = "drag")]
+    pub ghost: bool,
+
+    #[arg(long, default_value_t = false)]
+    pub timer: bool,
+
+    #[arg(long = "interval", value_name = "FROM-TO[@GEAR]")]
+    pub intervals: Vec<Benchmark>,
+
+    #[arg(long, default_value = ".")]
+    pub screenshots: PathBuf,
+
+    #[arg(long)]
+    pub capture_frames: Option<PathBuf>,
+
+    #[arg(long)]
+    pub record: Option<PathBuf>,
+
+    #[cfg(feature = "ttf")]
+    #[arg(long)]
+    pub font: Option<PathBuf>,
 }
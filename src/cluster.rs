@@ -0,0 +1,206 @@
+use chrono::{NaiveTime, Timelike};
+use clap::ValueEnum;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::atlas::Sprites;
+use crate::draw::{Peripherals, GHOST_ALPHA};
+use crate::drivetrain::{MAX_RPM, REDLINE_RPM};
+use crate::error::Error;
+use crate::text::{draw_text, Align, TextStyle};
+use crate::theme::{Hud, Theme};
+use crate::units::Units;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Cluster {
+    Analog,
+    Digital,
+}
+
+const SHIFT_LIGHTS: i16 = 10;
+const SHIFT_LIGHTS_RANGE: f64 = 2500.0;
+const YELLOW_ZONE: f64 = 1500.0;
+const SEGMENTS: i16 = 32;
+const SEGMENT_SPACING: i16 = 14;
+const GHOST_MARKER_WIDTH: i16 = 4;
+
+fn zone_color(hud: &Hud, rpm: f64) -> Color {
+    if rpm >= REDLINE_RPM {
+        hud.bad
+    } else if rpm >= REDLINE_RPM - YELLOW_ZONE {
+        hud.fair
+    } else {
+        hud.good
+    }
+    .into()
+}
+
+fn blinking(time: NaiveTime) -> bool {
+    time.nanosecond() % 250_000_000 < 125_000_000
+}
+
+fn shift_lights<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    theme: &Theme,
+    position: (i16, i16),
+    rpm: f64,
+    time: NaiveTime,
+) -> Result<(), Error> {
+    let start = REDLINE_RPM - SHIFT_LIGHTS_RANGE;
+    let progress = (rpm - start) / SHIFT_LIGHTS_RANGE;
+    let lit = (progress * f64::from(SHIFT_LIGHTS)).ceil() as i16;
+    let over_rev = rpm >= REDLINE_RPM;
+
+    for light in 0..SHIFT_LIGHTS {
+        let color = if light < 4 {
+            theme.hud.good
+        } else if light < 7 {
+            theme.hud.fair
+        } else {
+            theme.hud.bad
+        };
+        let on = if over_rev {
+            blinking(time)
+        } else {
+            light < lit
+        };
+        let color = Color::from(if on {
+            color
+        } else {
+            theme.colors.needle_shadow
+        });
+
+        canvas
+            .filled_circle(position.0 + 24 + light * 48, position.1 + 16, 12, color)
+            .map_err(Error::video)?;
+    }
+
+    Ok(())
+}
+
+fn rpm_bar<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    theme: &Theme,
+    position: (i16, i16),
+    rpm: f64,
+    ghost_rpm: Option<f64>,
+) -> Result<(), Error> {
+    for segment in 0..SEGMENTS {
+        let segment_rpm = f64::from(segment) / f64::from(SEGMENTS) * MAX_RPM;
+        let color = if rpm > segment_rpm {
+            zone_color(&theme.hud, segment_rpm)
+        } else {
+            theme.colors.needle_shadow.into()
+        };
+        let x = position.0 + segment * SEGMENT_SPACING;
+        let height = 32 + segment;
+
+        canvas
+            .box_(x, position.1 + 64 - height, x + 10, position.1 + 64, color)
+            .map_err(Error::video)?;
+    }
+
+    if let Some(ghost_rpm) = ghost_rpm {
+        let progress = (ghost_rpm / MAX_RPM).clamp(0.0, 1.0);
+        let x = position.0 + (progress * f64::from(SEGMENTS * SEGMENT_SPACING)) as i16;
+        let color = Color::from(theme.colors.needle);
+        canvas
+            .box_(
+                x - GHOST_MARKER_WIDTH / 2,
+                position.1,
+                x + GHOST_MARKER_WIDTH / 2,
+                position.1 + 64,
+                Color::RGBA(color.r, color.g, color.b, GHOST_ALPHA),
+            )
+            .map_err(Error::video)?;
+    }
+
+    Ok(())
+}
+
+pub fn digital<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    units: Units,
+    position: (i16, i16),
+    peripherals: &Peripherals,
+) -> Result<(), Error> {
+    let text = Color::from(theme.colors.text);
+    let (x, y) = position;
+
+    shift_lights(canvas, theme, position, peripherals.rpm, peripherals.time)?;
+    rpm_bar(
+        canvas,
+        theme,
+        (x, y + 48),
+        peripherals.rpm,
+        peripherals.ghost.as_ref().map(|ghost| ghost.rpm),
+    )?;
+
+    draw_text(
+        canvas,
+        sprites,
+        &format!("{:.0} RPM", peripherals.rpm.max(0.0)),
+        (x, y + 124),
+        &TextStyle::new(4, text),
+    )?;
+
+    draw_text(
+        canvas,
+        sprites,
+        peripherals.gear.label(),
+        (x + 640, y + 48),
+        &TextStyle::new(24, zone_color(&theme.hud, peripherals.rpm)).aligned(Align::Right),
+    )?;
+
+    draw_text(
+        canvas,
+        sprites,
+        &format!("{:.0}", units.speed(peripherals.kmh)),
+        (x, y + 164),
+        &TextStyle::new(10, text),
+    )?;
+    draw_text(
+        canvas,
+        sprites,
+        units.speed_label(),
+        (x + 200, y + 190),
+        &TextStyle::new(4, text),
+    )?;
+
+    draw_text(
+        canvas,
+        sprites,
+        &format!(
+            "ODO {:.1} {}",
+            units.distance(peripherals.odometer),
+            units.distance_label()
+        ),
+        (x, y + 240),
+        &TextStyle::new(4, text),
+    )?;
+    draw_text(
+        canvas,
+        sprites,
+        &format!(
+            "TRIP {:.1} {}",
+            units.distance(peripherals.trip),
+            units.distance_label()
+        ),
+        (x, y + 272),
+        &TextStyle::new(4, text),
+    )?;
+    draw_text(
+        canvas,
+        sprites,
+        &peripherals.time.format("%H:%M").to_string(),
+        (x + 640, y + 240),
+        &TextStyle::new(4, text).aligned(Align::Right),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,230 @@
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::draw::{gearstick_position, STICK_TRAVEL};
+use crate::error::Error;
+use crate::frame::Clock;
+use crate::gear_stick::{Gear, CENTER_LANE, GEAR_DEPTH};
+use crate::hand::{CENTER_GATE, CROSS_GATE, OUTER_GATE};
+use crate::input::{Action, ActionState};
+use crate::layout::{Anchor, Layout};
+use crate::player::{Player, GRAB_RADIUS};
+use crate::text::{draw_text, Align, TextStyle};
+use crate::theme::Theme;
+
+const WALL: Color = Color::RGBA(230, 24, 32, 96);
+const NEUTRAL: Color = Color::RGBA(240, 200, 16, 48);
+const GEAR_BOX: Color = Color::RGBA(32, 200, 64, 255);
+const HAND: Color = Color::RGBA(64, 160, 255, 255);
+const STICK: Color = Color::RGBA(255, 128, 0, 255);
+
+const LINE_HEIGHT: i16 = 20;
+
+pub fn point(origin: (i16, i16), (x, y): (f64, f64)) -> (i16, i16) {
+    (
+        origin.0 + 80 + (x * STICK_TRAVEL) as i16,
+        origin.1 + 80 + (y * STICK_TRAVEL) as i16,
+    )
+}
+
+pub fn gear_region(gear: Gear) -> Option<((f64, f64), (f64, f64))> {
+    let (x_min, x_max) = match gear {
+        Gear::Neutral => return None,
+        Gear::First | Gear::Second => (-1.0, -GEAR_DEPTH),
+        Gear::Third | Gear::Fourth => (-CENTER_LANE, CENTER_LANE),
+        Gear::Fifth | Gear::Rocket => (GEAR_DEPTH, 1.0),
+    };
+    let (y_min, y_max) = match gear {
+        Gear::First | Gear::Third | Gear::Fifth => (-1.0, -GEAR_DEPTH),
+        _ => (GEAR_DEPTH, 1.0),
+    };
+    Some(((x_min, y_min), (x_max, y_max)))
+}
+
+fn region<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    origin: (i16, i16),
+    from: (f64, f64),
+    to: (f64, f64),
+    color: Color,
+    filled: bool,
+) -> Result<(), Error> {
+    let (x1, y1) = point(origin, from);
+    let (x2, y2) = point(origin, to);
+    if filled {
+        canvas.box_(x1, y1, x2, y2, color)
+    } else {
+        canvas.rectangle(x1, y1, x2, y2, color)
+    }
+    .map_err(Error::video)
+}
+
+fn cross<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    origin: (i16, i16),
+    target: (f64, f64),
+    color: Color,
+) -> Result<(), Error> {
+    let (x, y) = point(origin, target);
+    canvas
+        .thick_line(x - 10, y - 10, x + 10, y + 10, 3, color)
+        .map_err(Error::video)?;
+    canvas
+        .thick_line(x - 10, y + 10, x + 10, y - 10, 3, color)
+        .map_err(Error::video)
+}
+
+fn gate<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    text: Color,
+    origin: (i16, i16),
+) -> Result<(), Error> {
+    region(
+        canvas,
+        origin,
+        (-1.0, -GEAR_DEPTH),
+        (1.0, GEAR_DEPTH),
+        NEUTRAL,
+        true,
+    )?;
+
+    for (x_min, x_max) in [(-OUTER_GATE, -CENTER_GATE), (CENTER_GATE, OUTER_GATE)] {
+        for (y_min, y_max) in [(-1.0, -CROSS_GATE), (CROSS_GATE, 1.0)] {
+            region(canvas, origin, (x_min, y_min), (x_max, y_max), WALL, true)?;
+        }
+    }
+
+    let gears = [
+        Gear::First,
+        Gear::Second,
+        Gear::Third,
+        Gear::Fourth,
+        Gear::Fifth,
+        Gear::Rocket,
+    ];
+    for gear in gears {
+        let Some((from, to)) = gear_region(gear) else {
+            continue;
+        };
+        region(canvas, origin, from, to, GEAR_BOX, false)?;
+
+        let label = point(origin, ((from.0 + to.0) / 2.0, from.1.signum() * 1.15));
+        draw_text(
+            canvas,
+            sprites,
+            gear.label(),
+            (label.0, label.1 - 5),
+            &TextStyle::new(2, text).aligned(Align::Center),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn state_label(state: Option<&ActionState>) -> String {
+    state.map_or_else(|| "-".to_string(), |state| format!("{state:?}"))
+}
+
+fn panel_lines(player: &Player, gear: &Gear, clock: &Clock) -> Vec<String> {
+    let input = &player.input;
+    let mut lines = vec![format!(
+        "fps {:.0} frame {:.1} ms",
+        clock.fps(),
+        clock.frame_time.as_secs_f64() * 1000.0
+    )];
+    lines.extend(
+        Action::ALL
+            .iter()
+            .map(|action| format!("{action:?} {}", state_label(input.get(action)))),
+    );
+
+    lines.push(format!(
+        "hand {:.2} {:.2} target {:.2} {:.2}",
+        player.hand.offset.0, player.hand.offset.1, player.hand.target.0, player.hand.target.1
+    ));
+    lines.push(format!(
+        "stick {:.2} {:.2} target {:.2} {:.2}",
+        player.gear_stick.offset.0,
+        player.gear_stick.offset.1,
+        player.gear_stick.target.0,
+        player.gear_stick.target.1
+    ));
+    lines.push(format!(
+        "gear {} held {}",
+        gear.label(),
+        player.gear_stick.held
+    ));
+    lines.push(format!(
+        "throttle {:.2} brake {:.2}",
+        input.speeder_alpha, input.brake_alpha
+    ));
+    lines.push(format!(
+        "cooldown {:.2} active {}",
+        player.clutch_cooldown.timer, player.clutch_cooldown.active
+    ));
+
+    lines
+}
+
+pub fn overlay<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    player: &Player,
+    gear: &Gear,
+    clock: &Clock,
+) -> Result<(), Error> {
+    let text = Color::from(theme.colors.text);
+    let origin = gearstick_position(layout);
+    gate(canvas, sprites, text, origin)?;
+
+    let (x, y) = point(origin, player.hand.offset);
+    canvas
+        .circle(x, y, (GRAB_RADIUS * STICK_TRAVEL) as i16, HAND)
+        .map_err(Error::video)?;
+    canvas.filled_circle(x, y, 6, HAND).map_err(Error::video)?;
+    cross(canvas, origin, player.hand.target, HAND)?;
+    cross(canvas, origin, player.gear_stick.target, STICK)?;
+
+    let cooldown = &player.clutch_cooldown;
+    let bar = (origin.0, origin.1 + 176);
+    canvas
+        .rectangle(bar.0, bar.1, bar.0 + 160, bar.1 + 12, STICK)
+        .map_err(Error::video)?;
+    if cooldown.active {
+        let filled = (cooldown.timer.clamp(0.0, 1.0) * 160.0) as i16;
+        canvas
+            .box_(bar.0, bar.1, bar.0 + filled, bar.1 + 12, STICK)
+            .map_err(Error::video)?;
+    }
+
+    let lines = panel_lines(player, gear, clock);
+    let (left, top) = layout.anchor(&Anchor::TopLeft, (16, 16));
+    let height = i16::try_from(lines.len()).map_err(Error::video)? * LINE_HEIGHT;
+    canvas
+        .box_(
+            left,
+            top,
+            left + 560,
+            top + height + 16,
+            Color::from(theme.hud.panel),
+        )
+        .map_err(Error::video)?;
+
+    let style = TextStyle::new(3, text);
+    for (index, line) in (0..).zip(&lines) {
+        draw_text(
+            canvas,
+            sprites,
+            line,
+            (left + 8, top + 8 + index * LINE_HEIGHT),
+            &style,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,413 @@
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::ghost::Sample;
+use crate::input::Action;
+use crate::layout::{Anchor, Layout};
+use crate::player::Player;
+use crate::simulation::TICK_SECONDS;
+use crate::text::{draw_text, text_width, Align, TextStyle};
+use crate::theme::{Hud, Rgb, Theme};
+use crate::units::Units;
+
+const STAGE_SECONDS: f64 = 1.0;
+const AMBER_SECONDS: f64 = 0.5;
+const AMBERS: u32 = 3;
+const GREEN_SECONDS: f64 = STAGE_SECONDS + AMBER_SECONDS * AMBERS as f64;
+
+const SIXTY_FEET: f64 = 18.288;
+const EIGHTH_MILE: f64 = 201.168;
+const QUARTER_MILE: f64 = 402.336;
+const TRAP_LENGTH: f64 = 20.1168;
+const MARKS: [f64; 4] = [
+    SIXTY_FEET,
+    EIGHTH_MILE,
+    QUARTER_MILE - TRAP_LENGTH,
+    QUARTER_MILE,
+];
+const SPLITS: [&str; 3] = ["60 ft", "1/8 mile", "1/4 mile"];
+
+const LIGHT_RADIUS: i16 = 24;
+const LIGHT_SPACING: i16 = 64;
+const PANEL_PADDING: i16 = 16;
+const LINE_HEIGHT: i16 = 36;
+
+#[derive(Default)]
+pub struct Run {
+    launch: Option<f64>,
+    distance: f64,
+    marks: [Option<f64>; 4],
+    trace: Vec<Sample>,
+}
+
+impl Run {
+    fn launched(player: &Player) -> bool {
+        player.input.action_active(&Action::Accelerate)
+            && !player.input.action_active(&Action::Clutch)
+            && player.gear() != Gear::Neutral
+    }
+
+    fn update(&mut self, time: f64, player: &Player) {
+        if self.finished() {
+            return;
+        }
+        let Some(launch) = self.launch else {
+            if Self::launched(player) {
+                self.launch = Some(time);
+                self.sample(player);
+            }
+            return;
+        };
+
+        let previous = self.distance;
+        self.distance += player.kmh.max(0.0) / 3.6 * TICK_SECONDS;
+        self.sample(player);
+
+        for (mark, crossed) in MARKS.iter().zip(&mut self.marks) {
+            if crossed.is_none() && self.distance >= *mark {
+                let fraction = (mark - previous) / (self.distance - previous);
+                *crossed = Some(time - TICK_SECONDS * (1.0 - fraction) - launch);
+            }
+        }
+    }
+
+    fn sample(&mut self, player: &Player) {
+        self.trace.push(Sample {
+            rpm: player.rpm,
+            kmh: player.kmh,
+            gear: player.gear(),
+            distance: self.distance,
+        });
+    }
+
+    pub fn trace(&self) -> &[Sample] {
+        &self.trace
+    }
+
+    pub fn clean_time(&self) -> Option<f64> {
+        self.marks[3].filter(|_| !self.foul())
+    }
+
+    fn reaction(&self) -> Option<f64> {
+        self.launch.map(|launch| launch - GREEN_SECONDS)
+    }
+
+    fn foul(&self) -> bool {
+        self.reaction().is_some_and(|reaction| reaction < 0.0)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.marks[3].is_some()
+    }
+
+    fn splits(&self) -> [Option<f64>; 3] {
+        [self.marks[0], self.marks[1], self.marks[3]]
+    }
+
+    fn trap_kmh(&self) -> Option<f64> {
+        let (Some(start), Some(end)) = (self.marks[2], self.marks[3]) else {
+            return None;
+        };
+        Some(TRAP_LENGTH / (end - start) * 3.6)
+    }
+
+    fn total(&self) -> Option<f64> {
+        Some(self.reaction()? + self.marks[3]?)
+    }
+
+    pub fn summary(&self) -> String {
+        let Some(reaction) = self.reaction() else {
+            return "no drag run".to_string();
+        };
+        let mut summary = format!("reaction {reaction:.3}s");
+        if self.foul() {
+            summary.push_str(" (red light)");
+        }
+        for (name, split) in SPLITS.iter().zip(self.splits()) {
+            if let Some(split) = split {
+                summary.push_str(&format!(", {name} {split:.3}s"));
+            }
+        }
+        match self.trap_kmh() {
+            Some(kmh) => summary.push_str(&format!(", trap {kmh:.1} km/h")),
+            None => summary.push_str(&format!(", stopped at {:.0} m", self.distance)),
+        }
+        summary
+    }
+}
+
+#[derive(Default)]
+pub struct Race {
+    ticks: u64,
+    pub runs: Vec<Run>,
+}
+
+impl Race {
+    pub fn update(&mut self, players: &[Player]) -> Vec<usize> {
+        self.ticks += 1;
+        let time = self.time();
+
+        if self.runs.len() < players.len() {
+            self.runs.resize_with(players.len(), Run::default);
+        }
+        let mut finished = Vec::new();
+        for (index, (run, player)) in self.runs.iter_mut().zip(players).enumerate() {
+            let running = !run.finished();
+            run.update(time, player);
+            if running && run.finished() {
+                finished.push(index);
+            }
+        }
+        finished
+    }
+
+    fn time(&self) -> f64 {
+        self.ticks as f64 * TICK_SECONDS
+    }
+
+    fn place(&self, index: usize) -> Option<(usize, usize)> {
+        let total = self.runs.get(index)?.total()?;
+        let finishers: Vec<f64> = self
+            .runs
+            .iter()
+            .filter(|run| !run.foul())
+            .filter_map(Run::total)
+            .collect();
+        if finishers.len() < 2 || self.runs[index].foul() {
+            return None;
+        }
+        let ahead = finishers.iter().filter(|other| **other < total).count();
+        Some((ahead + 1, finishers.len()))
+    }
+}
+
+fn place_label(place: usize) -> String {
+    let suffix = match place {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    };
+    format!("{place}{suffix}")
+}
+
+fn tree<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    hud: &Hud,
+    (x, y): (i16, i16),
+    time: f64,
+    run: Option<&Run>,
+) -> Result<(), Error> {
+    let foul = run.is_some_and(Run::foul);
+    let green = time >= GREEN_SECONDS;
+    let lit = |on: bool, color: Rgb| Color::from(if on { color } else { hud.unlit });
+
+    let mut lights = vec![lit(true, hud.stage)];
+    for amber in 0..AMBERS {
+        let on = time >= STAGE_SECONDS + AMBER_SECONDS * f64::from(amber);
+        lights.push(lit(on && !green, hud.amber));
+    }
+    lights.push(lit(green && !foul, hud.good));
+    lights.push(lit(foul, hud.bad));
+
+    let count = lights.len() as i16;
+    let left = x - (count - 1) * LIGHT_SPACING / 2;
+    canvas
+        .box_(
+            left - LIGHT_SPACING / 2,
+            y - LIGHT_SPACING / 2,
+            left + count * LIGHT_SPACING - LIGHT_SPACING / 2,
+            y + LIGHT_SPACING / 2,
+            Color::from(hud.panel),
+        )
+        .map_err(Error::video)?;
+    for (index, color) in (0..count).zip(lights) {
+        canvas
+            .filled_circle(left + index * LIGHT_SPACING, y, LIGHT_RADIUS, color)
+            .map_err(Error::video)?;
+    }
+    Ok(())
+}
+
+fn results(race: &Race, index: usize, run: &Run, units: Units) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(reaction) = run.reaction() {
+        lines.push(if run.foul() {
+            format!("red light {reaction:.3}s")
+        } else {
+            format!("reaction {reaction:.3}s")
+        });
+    }
+    for (name, split) in SPLITS.iter().zip(run.splits()) {
+        if let Some(split) = split {
+            lines.push(format!("{name} {split:.3}s"));
+        }
+    }
+    if let Some(kmh) = run.trap_kmh() {
+        lines.push(format!(
+            "trap {:.1} {}",
+            units.speed(kmh),
+            units.speed_label()
+        ));
+    }
+    if let Some((place, of)) = race.place(index) {
+        lines.push(format!("{} of {of}", place_label(place)));
+    }
+    lines
+}
+
+pub fn draw<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    units: Units,
+    race: &Race,
+    index: usize,
+) -> Result<(), Error> {
+    let time = race.time();
+    let run = race.runs.get(index);
+    let (x, y) = layout.anchor(&Anchor::Center, (0, -360));
+    tree(canvas, &theme.hud, (x, y), time, run)?;
+
+    let style = TextStyle::new(3, theme.colors.text.into()).aligned(Align::Center);
+    let Some(run) = run.filter(|run| run.finished()) else {
+        let status = match run.and_then(|run| Some((run.launch?, run.distance))) {
+            Some((launch, distance)) => format!(
+                "{:.2}s  {:.3} {}",
+                time - launch,
+                units.distance(distance / 1000.0),
+                units.distance_label()
+            ),
+            None if time < GREEN_SECONDS => "staged".to_string(),
+            None => "go".to_string(),
+        };
+        return draw_text(
+            canvas,
+            sprites,
+            &status,
+            (x, y + LIGHT_SPACING / 2 + PANEL_PADDING),
+            &style,
+        );
+    };
+
+    let lines = results(race, index, run, units);
+    let width = lines
+        .iter()
+        .map(|line| text_width(sprites, line, &style))
+        .max()
+        .unwrap_or_default();
+    let half = i16::try_from(width / 2).unwrap_or(i16::MAX / 2) + PANEL_PADDING;
+    let top = y + LIGHT_SPACING / 2 + PANEL_PADDING;
+    let height = lines.len() as i16 * LINE_HEIGHT + PANEL_PADDING * 2;
+
+    canvas
+        .box_(
+            x - half,
+            top,
+            x + half,
+            top + height,
+            Color::from(theme.hud.panel),
+        )
+        .map_err(Error::video)?;
+    for (row, line) in (0..).zip(&lines) {
+        draw_text(
+            canvas,
+            sprites,
+            line,
+            (x, top + PANEL_PADDING + row * LINE_HEIGHT),
+            &style,
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Input;
+
+    const FIRST_GEAR: (f64, f64) = (-1.0, -1.0);
+    const TOLERANCE: f64 = 1e-6;
+
+    fn driver(kmh: f64) -> Player {
+        let mut player = Player::new(Input::with_sensitivity(128.0));
+        player.gear_stick.offset = FIRST_GEAR;
+        player.gear_stick.target = FIRST_GEAR;
+        player.kmh = kmh;
+        player
+    }
+
+    /// Runs a race in which every player holds a fixed speed and launches on
+    /// the given tick, until all of them have finished.
+    fn race(players: &mut [Player], launch_ticks: &[u64]) -> Race {
+        let mut race = Race::default();
+        while race.runs.len() < players.len() || !race.runs.iter().all(Run::finished) {
+            for (player, launch) in players.iter_mut().zip(launch_ticks) {
+                if race.ticks + 1 >= *launch {
+                    player.input.key_down(Action::Accelerate);
+                }
+            }
+            race.update(players);
+        }
+        race
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("missing time");
+        assert!(
+            (actual - expected).abs() < TOLERANCE,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn times_the_splits_and_trap_speed_at_a_fixed_speed() {
+        let kmh = 72.0;
+        let speed = kmh / 3.6;
+        let launch = 180;
+        let race = race(&mut [driver(kmh)], &[launch]);
+        let run = &race.runs[0];
+
+        assert_close(run.marks[0], SIXTY_FEET / speed);
+        assert_close(run.marks[1], EIGHTH_MILE / speed);
+        assert_close(run.marks[3], QUARTER_MILE / speed);
+        assert_close(run.trap_kmh(), kmh);
+        assert_close(run.reaction(), launch as f64 * TICK_SECONDS - GREEN_SECONDS);
+        assert!(!run.foul());
+        assert_close(run.clean_time(), QUARTER_MILE / speed);
+    }
+
+    #[test]
+    fn launching_before_the_green_is_a_red_light() {
+        let race = race(&mut [driver(72.0)], &[60]);
+        let run = &race.runs[0];
+
+        assert!(run.finished());
+        assert!(run.foul());
+        assert!(run.reaction().is_some_and(|reaction| reaction < 0.0));
+        assert!(run.clean_time().is_none());
+        assert!(run.summary().contains("(red light)"));
+    }
+
+    #[test]
+    fn places_clean_runs_by_total_time() {
+        let mut players = [driver(100.0), driver(80.0), driver(120.0)];
+        let race = race(&mut players, &[160, 160, 60]);
+
+        assert_eq!(race.place(0), Some((1, 2)));
+        assert_eq!(race.place(1), Some((2, 2)));
+        assert_eq!(race.place(2), None);
+    }
+
+    #[test]
+    fn a_lone_finisher_has_no_place() {
+        let race = race(&mut [driver(100.0)], &[160]);
+
+        assert_eq!(race.place(0), None);
+    }
+}
@@ -1,9 +1,21 @@
+use std::collections::VecDeque;
+
+use chrono::NaiveTime;
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use sdl2::render::{Texture, WindowCanvas};
+use sdl2::render::{Canvas, RenderTarget};
 
+use crate::atlas::Sprites;
+use crate::cluster::{self, Cluster};
+use crate::drivetrain::MAX_RPM;
+use crate::error::Error;
 use crate::gear_stick::Gear;
+use crate::layout::{ui_scale, Anchor, Layout};
+use crate::player::PedalTravel;
+use crate::text::{draw_text, Align, TextStyle};
+use crate::theme::{Gauge, Hud, Theme};
+use crate::units::Units;
 
 macro_rules! rect(
     ($x:expr, $y:expr, $w:expr, $h:expr) => (
@@ -11,34 +23,64 @@ macro_rules! rect(
     )
 );
 
-fn gearstick(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+pub const STICK_TRAVEL: f64 = 128.0;
+pub const GHOST_ALPHA: u8 = 96;
+
+pub fn gearstick_position(layout: &Layout) -> (i16, i16) {
+    layout.anchor(&Anchor::BottomRight, (-512, -336))
+}
+
+pub const PEDAL_SIZE: i16 = 160;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Pedal {
+    Clutch,
+    Brake,
+    Throttle,
+}
+
+pub fn pedal_position(layout: &Layout, pedal: Pedal) -> (i16, i16) {
+    let column = match pedal {
+        Pedal::Clutch => -1,
+        Pedal::Brake => 0,
+        Pedal::Throttle => 1,
+    };
+    layout.anchor(&Anchor::Bottom, (-80 + column * PEDAL_SIZE, -240))
+}
+
+fn gearstick<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    color: Color,
     position: (i16, i16),
     offset: (f64, f64),
-) -> Result<(), String> {
-    canvas.copy(
-        texture,
-        rect!(128, 0, 64, 64),
+) -> Result<(), Error> {
+    sprites.draw(
+        canvas,
+        "shifter_base",
         rect!(position.0, position.1, 160, 160),
     )?;
 
     let start_x = position.0 + 80;
     let start_y = position.1 + 80;
-    let end_x = position.0 + 80 + (offset.0 * 128.0) as i16;
-    let end_y = position.1 + 80 + (offset.1 * 128.0) as i16;
+    let end_x = position.0 + 80 + (offset.0 * STICK_TRAVEL) as i16;
+    let end_y = position.1 + 80 + (offset.1 * STICK_TRAVEL) as i16;
 
     if !(start_x == end_x && start_y == end_y) {
-        canvas.filled_circle(start_x, start_y, 32, Color::RGB(178, 16, 48))?;
-        canvas.thick_line(start_x, start_y, end_x, end_y, 64, Color::RGB(178, 16, 48))?;
+        canvas
+            .filled_circle(start_x, start_y, 32, color)
+            .map_err(Error::video)?;
+        canvas
+            .thick_line(start_x, start_y, end_x, end_y, 64, color)
+            .map_err(Error::video)?;
     }
 
-    canvas.copy(
-        texture,
-        rect!(64, 0, 64, 64),
+    sprites.draw(
+        canvas,
+        "gear_knob",
         rect!(
-            f64::from(position.0) + offset.0 * 128.0,
-            f64::from(position.1) + offset.1 * 128.0,
+            f64::from(position.0) + offset.0 * STICK_TRAVEL,
+            f64::from(position.1) + offset.1 * STICK_TRAVEL,
             160,
             160
         ),
@@ -47,21 +89,25 @@ fn gearstick(
     Ok(())
 }
 
-fn hand(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+fn hand<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
     position: (i16, i16),
     offset: (f64, f64),
     grabbing: bool,
-) -> Result<(), String> {
-    let sprite_offset = if grabbing { 64 } else { 0 };
+) -> Result<(), Error> {
+    let sprite = if grabbing {
+        "hand_grabbing"
+    } else {
+        "hand_open"
+    };
 
-    canvas.copy(
-        texture,
-        rect!(sprite_offset, 64, 64, 64),
+    sprites.draw(
+        canvas,
+        sprite,
         rect!(
-            f64::from(position.0) + offset.0 * 128.0,
-            f64::from(position.1) + offset.1 * 128.0,
+            f64::from(position.0) + offset.0 * STICK_TRAVEL,
+            f64::from(position.1) + offset.1 * STICK_TRAVEL,
             128 + 32,
             128 + 32
         ),
@@ -70,271 +116,409 @@ fn hand(
     Ok(())
 }
 
-pub struct Pedals {
-    pub clutch_down: bool,
-    pub speeder_down: bool,
-    pub brake_down: bool,
+const TRACE_HEIGHT: i16 = 48;
+
+type PedalValue = fn(&PedalTravel) -> f64;
+
+pub struct Pedals<'a> {
+    pub travel: PedalTravel,
+    pub trace: Option<&'a VecDeque<PedalTravel>>,
 }
 
-fn pedals(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+fn pedal<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    color: Color,
     position: (i16, i16),
-    Pedals {
-        clutch_down,
-        speeder_down,
-        brake_down,
-    }: &Pedals,
-) -> Result<(), String> {
-    let size = 160;
-
-    let texture_x = if *clutch_down { 224 } else { 192 };
-
-    canvas.copy(
-        texture,
-        rect!(texture_x, 32, 32, 32),
-        rect!(position.0 - size, position.1, size, size),
-    )?;
-
-    let texture_x = if *brake_down { 224 } else { 192 };
+    size: i16,
+    travel: f64,
+) -> Result<(), Error> {
+    let travel = travel.clamp(0.0, 1.0);
+    let sprite = if travel > 0.5 {
+        "pedal_down"
+    } else {
+        "pedal_up"
+    };
+    let sink = (f64::from(size / 4) * travel) as i16;
 
-    canvas.copy(
-        texture,
-        rect!(texture_x, 32, 32, 32),
-        rect!(position.0, position.1, size, size),
+    sprites.draw(
+        canvas,
+        sprite,
+        rect!(position.0, position.1 + sink, size, size - sink),
     )?;
 
-    let texture_x = if *speeder_down { 224 } else { 192 };
-
-    canvas.copy(
-        texture,
-        rect!(texture_x, 32, 32, 32),
-        rect!(position.0 + size, position.1, size, size),
-    )?;
+    let level = (f64::from(size) * travel) as i16;
+    if level > 0 {
+        canvas
+            .box_(
+                position.0,
+                position.1 + size - level,
+                position.0 + 6,
+                position.1 + size,
+                color,
+            )
+            .map_err(Error::video)?;
+    }
 
     Ok(())
 }
 
-fn gear_state(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+fn pedal_trace<T: RenderTarget, F>(
+    canvas: &mut Canvas<T>,
+    color: Color,
     position: (i16, i16),
-    gear: &Gear,
-) -> Result<(), String> {
-    let initial_x = 128;
-    let initial_y = 64;
-
-    let y = match gear {
-        Gear::Neutral => 0,
-        Gear::Rocket => 1,
-        Gear::First => 2,
-        Gear::Second => 3,
-        Gear::Third => 4,
-        Gear::Fourth => 5,
-        Gear::Fifth => 6,
+    width: i16,
+    trace: &VecDeque<PedalTravel>,
+    value: F,
+) -> Result<(), Error>
+where
+    F: Fn(&PedalTravel) -> f64,
+{
+    let bottom = position.1 + TRACE_HEIGHT;
+    canvas
+        .rectangle(position.0, position.1, position.0 + width, bottom, color)
+        .map_err(Error::video)?;
+
+    let step = f64::from(width) / trace.len().max(2).saturating_sub(1) as f64;
+    let point = |index: usize, sample: &PedalTravel| {
+        (
+            position.0 + (index as f64 * step) as i16,
+            bottom - (value(sample).clamp(0.0, 1.0) * f64::from(TRACE_HEIGHT)) as i16,
+        )
     };
 
-    canvas.copy(
-        texture,
-        rect!(initial_x, initial_y + y * 6, 24, 5),
-        rect!(position.0, position.1, 192, 40),
-    )?;
+    for (index, (from, to)) in trace.iter().zip(trace.iter().skip(1)).enumerate() {
+        let start = point(index, from);
+        let end = point(index + 1, to);
+        canvas
+            .thick_line(start.0, start.1, end.0, end.1, 2, color)
+            .map_err(Error::video)?;
+    }
 
     Ok(())
 }
 
-#[derive(Clone)]
-enum Digit {
-    Zero,
-    One,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    None,
-}
-
-impl Digit {
-    fn digit_to_texture(&self, negative: bool) -> (u32, u32) {
-        let (x, y) = match self {
-            Digit::Zero => (0, 0),
-            Digit::One => (1, 0),
-            Digit::Two => (2, 0),
-            Digit::Three => (3, 0),
-            Digit::Four => (0, 1),
-            Digit::Five => (1, 1),
-            Digit::Six => (2, 1),
-            Digit::Seven => (3, 1),
-            Digit::Eight => (0, 2),
-            Digit::Nine => (1, 2),
-            Digit::None => (2, 2),
-        };
-
-        let start_x = if negative { 176 } else { 160 };
-        let start_y = 64;
-
-        let x = (x * 4) + start_x;
-        let y = y * 5 + start_y;
-
-        (x, y)
+fn pedals<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    color: Color,
+    layout: &Layout,
+    Pedals { travel, trace }: &Pedals,
+) -> Result<(), Error> {
+    let size = PEDAL_SIZE;
+
+    let pedals: [(Pedal, PedalValue); 3] = [
+        (Pedal::Clutch, |pedals| pedals.clutch),
+        (Pedal::Brake, |pedals| pedals.brake),
+        (Pedal::Throttle, |pedals| pedals.throttle),
+    ];
+
+    for (which, value) in pedals {
+        let (x, y) = pedal_position(layout, which);
+        pedal(canvas, sprites, color, (x, y), size, value(travel))?;
+
+        if let Some(trace) = trace {
+            pedal_trace(
+                canvas,
+                color,
+                (x + 8, y - TRACE_HEIGHT - 8),
+                size - 16,
+                trace,
+                value,
+            )?;
+        }
     }
+
+    Ok(())
 }
 
-impl From<i64> for Digit {
-    fn from(value: i64) -> Self {
-        let values = [
-            Self::Zero,
-            Self::One,
-            Self::Two,
-            Self::Three,
-            Self::Four,
-            Self::Five,
-            Self::Six,
-            Self::Seven,
-            Self::Eight,
-            Self::Nine,
-        ];
-        if !(0..=9).contains(&value) {
-            unreachable!("value {value} should be 0 >= {value} >= 9");
-        }
-        values[value.unsigned_abs() as usize].clone()
+fn gear_sprite(gear: &Gear) -> &'static str {
+    match gear {
+        Gear::Neutral => "gear_neutral",
+        Gear::Rocket => "gear_rocket",
+        Gear::First => "gear_first",
+        Gear::Second => "gear_second",
+        Gear::Third => "gear_third",
+        Gear::Fourth => "gear_fourth",
+        Gear::Fifth => "gear_fifth",
     }
 }
 
-fn get_digit(value: i64, digit_place: i64) -> Digit {
-    if value >= digit_place {
-        let value = value % (digit_place * 10);
-        let digit = (value - value % digit_place) / digit_place;
-        let digit: Digit = digit.into();
-        digit
-    } else {
-        Digit::None
-    }
+fn gear_state<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    position: (i16, i16),
+    gear: &Gear,
+) -> Result<(), Error> {
+    sprites.draw(
+        canvas,
+        gear_sprite(gear),
+        rect!(position.0, position.1, 192, 40),
+    )?;
+
+    Ok(())
 }
 
-fn draw_digit(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
-    digit: &Digit,
+fn ghost_state<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    hud: &Hud,
     position: (i16, i16),
-    negative: bool,
-) -> Result<(), String> {
-    let (texture_x, texture_y) = digit.digit_to_texture(negative);
-
-    canvas.copy(
-        texture,
-        rect!(texture_x, texture_y, 3, 5),
-        rect!(position.0, position.1, 24, 40),
+    ghost: &Ghost,
+) -> Result<(), Error> {
+    sprites.draw_faded(
+        canvas,
+        gear_sprite(&ghost.gear),
+        rect!(position.0, position.1, 192, 40),
+        GHOST_ALPHA,
     )?;
 
+    if let Some(delta) = ghost.delta {
+        let color = Color::from(if delta > 0.0 { hud.bad } else { hud.good });
+        draw_text(
+            canvas,
+            sprites,
+            &format!("{delta:+.2}s"),
+            (position.0 + 192 + 16, position.1 + 4),
+            &TextStyle::new(4, color),
+        )?;
+    }
+
     Ok(())
 }
 
-fn speedometer(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+fn speedometer<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    units: Units,
     position: (i16, i16),
     kmh: f64,
-) -> Result<(), String> {
-    let kmh: i64 = kmh.round() as i64;
-    let negative = kmh.is_negative();
-    let kmh = kmh.abs();
-    let first_digit: Digit = (kmh % 10).into();
-    let second_digit = get_digit(kmh, 10);
-    let third_digit = get_digit(kmh, 100);
-
-    canvas.copy(
-        texture,
-        rect!(128, 106, 20, 5),
-        rect!(position.0, position.1, 160, 40),
-    )?;
+) -> Result<(), Error> {
+    let speed: i64 = units.speed(kmh).round() as i64;
 
-    draw_digit(
+    sprites.draw(
         canvas,
-        texture,
-        &first_digit,
-        (position.0 + 160 + 96 - 8, position.1),
-        negative,
+        "speed_label",
+        rect!(position.0, position.1, 160, 40),
     )?;
-    draw_digit(
+
+    draw_text(
         canvas,
-        texture,
-        &second_digit,
-        (position.0 + 160 + 64 - 8, position.1),
-        negative && (0..10).contains(&kmh),
+        sprites,
+        &speed.to_string(),
+        (position.0 + 160 + 96 + 16, position.1),
+        &TextStyle::new(8, theme.colors.text.into()).aligned(Align::Right),
     )?;
-    draw_digit(
+
+    draw_text(
         canvas,
-        texture,
-        &third_digit,
-        (position.0 + 160 + 32 - 8, position.1),
-        negative && (10..100).contains(&kmh),
+        sprites,
+        units.speed_label(),
+        (position.0 + 160 + 96 + 32, position.1 + 20),
+        &TextStyle::new(4, theme.colors.text.into()),
     )?;
 
     Ok(())
 }
 
-fn tachometer(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
+fn gauge_angle(gauge: &Gauge, percentage: f64) -> f64 {
+    let angle = (percentage * (gauge.max_angle - gauge.min_angle)) + gauge.min_angle;
+    angle.to_radians()
+}
+
+fn needle<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    theme: &Theme,
+    gauge: &Gauge,
+    center: (i16, i16),
+    percentage: f64,
+) -> Result<(), Error> {
+    let Gauge {
+        needle_length,
+        needle_width,
+        shadow_length,
+        shadow_width,
+        ..
+    } = *gauge;
+    let angle = gauge_angle(gauge, percentage);
+
+    let offset = (angle.sin() * needle_length, angle.cos() * needle_length);
+    let background_offset = (angle.sin() * shadow_length, angle.cos() * shadow_length);
+    let target = (center.0 + offset.0 as i16, center.1 + offset.1 as i16);
+    let background_target = (
+        center.0 + background_offset.0 as i16,
+        center.1 + background_offset.1 as i16,
+    );
+    canvas
+        .thick_line(
+            center.0,
+            center.1,
+            background_target.0,
+            background_target.1,
+            shadow_width,
+            Color::from(theme.colors.needle_shadow),
+        )
+        .map_err(Error::video)?;
+
+    canvas
+        .thick_line(
+            center.0,
+            center.1,
+            target.0,
+            target.1,
+            needle_width,
+            Color::from(theme.colors.needle),
+        )
+        .map_err(Error::video)?;
+
+    Ok(())
+}
+
+fn ghost_needle<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    theme: &Theme,
+    gauge: &Gauge,
+    center: (i16, i16),
+    percentage: f64,
+) -> Result<(), Error> {
+    let angle = gauge_angle(gauge, percentage);
+    let target = (
+        center.0 + (angle.sin() * gauge.needle_length) as i16,
+        center.1 + (angle.cos() * gauge.needle_length) as i16,
+    );
+    let color = Color::from(theme.colors.needle);
+
+    canvas
+        .thick_line(
+            center.0,
+            center.1,
+            target.0,
+            target.1,
+            gauge.needle_width,
+            Color::RGBA(color.r, color.g, color.b, GHOST_ALPHA),
+        )
+        .map_err(Error::video)?;
+
+    Ok(())
+}
+
+fn tachometer<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
     position: (i16, i16),
     rpm: f64,
-) -> Result<(), String> {
+    ghost_rpm: Option<f64>,
+) -> Result<(), Error> {
     let min_rpm = 0.0;
-    let max_rpm = 8000.0;
-    let min_angle = -12.5;
-    let max_angle = -347.5;
-
-    let percentage = (rpm - min_rpm) / (max_rpm - min_rpm);
 
-    let angle = (percentage * (max_angle - min_angle)) + min_angle;
-    let angle = angle.to_radians();
+    let percentage = |rpm: f64| (rpm - min_rpm) / (MAX_RPM - min_rpm);
 
-    canvas.copy(
-        texture,
-        rect!(0, 0, 64, 64),
+    sprites.draw(
+        canvas,
+        "tachometer",
         rect!(position.0, position.1, 256, 256),
     )?;
 
     let center = (position.0 + 128, position.1 + 128);
-    let offset = (angle.sin() * 116.0, angle.cos() * 116.0);
-    let background_offset = (angle.sin() * 118.0, angle.cos() * 118.0);
-    let target = (center.0 + offset.0 as i16, center.1 + offset.1 as i16);
-    let background_target = (
-        center.0 + background_offset.0 as i16,
-        center.1 + background_offset.1 as i16,
-    );
-    canvas.thick_line(
-        center.0,
-        center.1,
-        background_target.0,
-        background_target.1,
-        8,
-        Color::RGB(127, 127, 127),
-    )?;
+    if let Some(ghost_rpm) = ghost_rpm {
+        ghost_needle(
+            canvas,
+            theme,
+            &theme.tachometer,
+            center,
+            percentage(ghost_rpm),
+        )?;
+    }
+    needle(canvas, theme, &theme.tachometer, center, percentage(rpm))
+}
 
-    canvas.thick_line(
-        center.0,
-        center.1,
-        target.0,
-        target.1,
-        4,
-        Color::RGB(178, 16, 48),
+fn speedometer_dial<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    units: Units,
+    position: (i16, i16),
+    kmh: f64,
+) -> Result<(), Error> {
+    let gauge = &theme.speedometer;
+    let max_speed = units.speedometer_max();
+    let text = Color::from(theme.colors.text);
+    let center = (position.0 + 128, position.1 + 128);
+
+    canvas
+        .filled_circle(center.0, center.1, 128, Color::from(theme.colors.dial))
+        .map_err(Error::video)?;
+
+    let mut speed = 0;
+    while f64::from(speed) <= max_speed {
+        let angle = gauge_angle(gauge, f64::from(speed) / max_speed);
+        let major = speed % 20 == 0;
+        let inner = if major { 100.0 } else { 110.0 };
+        let point = |length: f64| {
+            (
+                center.0 + (angle.sin() * length) as i16,
+                center.1 + (angle.cos() * length) as i16,
+            )
+        };
+        let (start, end) = (point(inner), point(120.0));
+        canvas
+            .thick_line(start.0, start.1, end.0, end.1, 3, text)
+            .map_err(Error::video)?;
+
+        if major {
+            let label = point(80.0);
+            draw_text(
+                canvas,
+                sprites,
+                &speed.to_string(),
+                (label.0, label.1 - 5),
+                &TextStyle::new(2, text).aligned(Align::Center),
+            )?;
+        }
+        speed += 10;
+    }
+
+    draw_text(
+        canvas,
+        sprites,
+        units.speed_label(),
+        (center.0, center.1 + 48),
+        &TextStyle::new(3, text).aligned(Align::Center),
     )?;
 
-    Ok(())
+    needle(canvas, theme, gauge, center, units.speed(kmh) / max_speed)
 }
 
-fn padded_end(max: i16, length: i16) -> i16 {
-    max - 256 - length / 2
+pub fn pane<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    window_size: (i16, i16),
+    index: i16,
+    count: i16,
+    scale: Option<f64>,
+) -> Result<Layout, Error> {
+    let (width, height) = window_size;
+    let pane_width = width / count;
+    let scale = scale.unwrap_or_else(|| ui_scale((pane_width, height)));
+    let layout = Layout::new((pane_width, height), scale).map_err(|e| Error::config(None, e))?;
+
+    canvas
+        .set_scale(scale as f32, scale as f32)
+        .map_err(Error::video)?;
+    canvas.set_viewport(rect!(
+        (f64::from(pane_width) * f64::from(index) / scale).round(),
+        0,
+        layout.size.0,
+        layout.size.1
+    ));
+
+    Ok(layout)
 }
 
-fn center(max: i16, length: i16) -> i16 {
-    (max / 2) - length / 2
+pub fn reset_pane<T: RenderTarget>(canvas: &mut Canvas<T>) -> Result<(), Error> {
+    canvas.set_viewport(None);
+    canvas.set_scale(1.0, 1.0).map_err(Error::video)
 }
 
 pub struct Hand {
@@ -342,31 +526,72 @@ pub struct Hand {
     pub grabbing: bool,
 }
 
+pub struct Options {
+    pub cluster: Cluster,
+    pub units: Units,
+}
+
+pub struct Ghost {
+    pub rpm: f64,
+    pub gear: Gear,
+    pub delta: Option<f64>,
+}
+
 pub struct Peripherals<'a> {
     pub rpm: f64,
     pub kmh: f64,
     pub gear: &'a Gear,
+    pub odometer: f64,
+    pub trip: f64,
+    pub time: NaiveTime,
+    pub ghost: Option<Ghost>,
 }
 
-pub fn all(
-    canvas: &mut WindowCanvas,
-    texture: &Texture,
-    window_size: (i16, i16),
-    Peripherals { rpm, kmh, gear }: &Peripherals,
+#[allow(clippy::too_many_arguments)]
+pub fn all<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    Options { cluster, units }: &Options,
+    layout: &Layout,
+    peripherals: &Peripherals,
     gear_offset: (f64, f64),
     hand_state: &Hand,
     pedal_state: &Pedals,
-) -> Result<(), String> {
-    let (width, height) = window_size;
-    let gearstick_position = (width - 128 * 4, padded_end(height, 160));
-
-    tachometer(canvas, texture, (128, padded_end(height, 256)), *rpm)?;
+) -> Result<(), Error> {
+    let gearstick_position = gearstick_position(layout);
+
+    if *cluster == Cluster::Analog {
+        tachometer(
+            canvas,
+            sprites,
+            theme,
+            layout.anchor(&Anchor::BottomLeft, (128, -384)),
+            peripherals.rpm,
+            peripherals.ghost.as_ref().map(|ghost| ghost.rpm),
+        )?;
+
+        speedometer_dial(
+            canvas,
+            sprites,
+            theme,
+            *units,
+            layout.anchor(&Anchor::BottomLeft, (448, -384)),
+            peripherals.kmh,
+        )?;
+    }
 
-    gearstick(canvas, texture, gearstick_position, gear_offset)?;
+    gearstick(
+        canvas,
+        sprites,
+        theme.colors.stick.into(),
+        gearstick_position,
+        gear_offset,
+    )?;
 
     hand(
         canvas,
-        texture,
+        sprites,
         gearstick_position,
         hand_state.offset,
         hand_state.grabbing,
@@ -374,24 +599,49 @@ pub fn all(
 
     pedals(
         canvas,
-        texture,
-        (center(width, 160), padded_end(height, 160) + 96),
+        sprites,
+        theme.colors.needle.into(),
+        layout,
         pedal_state,
     )?;
 
-    gear_state(
-        canvas,
-        texture,
-        (center(width, 192), padded_end(height, 128) - 64),
-        gear,
-    )?;
+    match cluster {
+        Cluster::Analog => {
+            gear_state(
+                canvas,
+                sprites,
+                layout.anchor(&Anchor::Bottom, (-96, -384)),
+                peripherals.gear,
+            )?;
+
+            speedometer(
+                canvas,
+                sprites,
+                theme,
+                *units,
+                layout.anchor(&Anchor::Bottom, (-128, -448)),
+                peripherals.kmh,
+            )?;
+        }
+        Cluster::Digital => cluster::digital(
+            canvas,
+            sprites,
+            theme,
+            *units,
+            layout.anchor(&Anchor::BottomLeft, (64, -448)),
+            peripherals,
+        )?,
+    }
 
-    speedometer(
-        canvas,
-        texture,
-        (center(width, 160 + 96), padded_end(height, 128) - 128),
-        *kmh,
-    )?;
+    if let Some(ghost) = &peripherals.ghost {
+        ghost_state(
+            canvas,
+            sprites,
+            &theme.hud,
+            layout.anchor(&Anchor::Bottom, (112, -384)),
+            ghost,
+        )?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,112 @@
+use crate::gear_stick::{expected_kmh, expected_rpm, Gear};
+use crate::input::{Action, Input};
+use crate::utils::lerp_1d;
+
+pub const IDLE_RPM: f64 = 700.0;
+pub const REDLINE_RPM: f64 = 7000.0;
+pub const MAX_RPM: f64 = 8000.0;
+const SLIP_RATE: f64 = 0.2;
+
+pub fn flywheel_rpm(rpm: f64, input: &mut Input, neutral_gear: bool) -> f64 {
+    let speeder_down = input.action_active(&Action::Accelerate);
+    let speeder_alpha = input.speeder_alpha;
+
+    let rpm_to_accel = |rpm: f64| -rpm.powf(1.3) + 1.8 * rpm + 1.0;
+    let rpm_to_deaccel = |rpm: f64| rpm.powf(1.1) + 1.0;
+
+    let acceleration_rate = rpm_to_accel(rpm / 1000.0);
+    let deacceleration_rate = if neutral_gear {
+        rpm_to_deaccel(rpm / 1000.0)
+    } else {
+        1.0
+    };
+
+    let rpm = if speeder_down {
+        rpm + (1500.0 / 60.0) * acceleration_rate * speeder_alpha
+    } else {
+        rpm - 500.0 / 60.0 * deacceleration_rate
+    };
+
+    if IDLE_RPM > rpm {
+        IDLE_RPM
+    } else if rpm > MAX_RPM {
+        input.shake_controller();
+        MAX_RPM - 100.0
+    } else {
+        rpm
+    }
+}
+
+pub struct ClutchCooldown {
+    pub active: bool,
+    pub start_rpm: f64,
+    pub timer: f64,
+}
+
+impl Default for ClutchCooldown {
+    fn default() -> Self {
+        Self {
+            start_rpm: 0.0,
+            timer: 0.0,
+            active: false,
+        }
+    }
+}
+
+pub struct KmhRpmPair {
+    pub kmh: f64,
+    pub rpm: f64,
+}
+
+pub fn brake(kmh: f64, rpm: f64, gear: &Gear, brake_alpha: f64) -> KmhRpmPair {
+    let kmh_diff = (50.0 / 60.0) * brake_alpha;
+
+    let rpm_diff = if gear == &Gear::Neutral {
+        0.0
+    } else {
+        let rpm_before = expected_rpm(kmh, gear.gear_ratio());
+        let rpm_after = expected_rpm(kmh - kmh_diff, gear.gear_ratio());
+        rpm_before - rpm_after
+    };
+
+    KmhRpmPair {
+        kmh: kmh - kmh_diff,
+        rpm: rpm - rpm_diff,
+    }
+}
+
+pub fn switch_into_gear(
+    kmh: f64,
+    clutch_cooldown: &ClutchCooldown,
+    input: &mut Input,
+    gear: &Gear,
+) -> KmhRpmPair {
+    let target = expected_rpm(kmh, gear.gear_ratio());
+    let rpm = lerp_1d(clutch_cooldown.timer, clutch_cooldown.start_rpm, target);
+
+    if (rpm - target).abs() > 500.0 {
+        input.shake_controller();
+    }
+
+    KmhRpmPair { kmh, rpm }
+}
+
+pub fn slip_clutch(kmh: f64, rpm: f64, gear: &Gear, engagement: f64) -> KmhRpmPair {
+    let rate = engagement.clamp(0.0, 1.0) * SLIP_RATE;
+    let target_rpm = expected_rpm(kmh, gear.gear_ratio());
+    let target_kmh = expected_kmh(rpm, gear.gear_ratio());
+
+    KmhRpmPair {
+        kmh: kmh + (target_kmh - kmh) * rate,
+        rpm: rpm + (target_rpm - rpm) * rate,
+    }
+}
+
+pub fn update_rpm_in_neutral(kmh: f64, rpm: f64, input: &mut Input) -> KmhRpmPair {
+    let rpm = flywheel_rpm(rpm, input, true);
+    let kmh = kmh - (1.0 / 60.0);
+    let min = expected_kmh(IDLE_RPM, Gear::Neutral.gear_ratio());
+    let kmh = if kmh < min { min } else { kmh };
+
+    KmhRpmPair { kmh, rpm }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub enum Error {
+    Asset {
+        path: PathBuf,
+        message: String,
+    },
+    Video(String),
+    Input(String),
+    Config {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    Simulation {
+        path: Option<PathBuf>,
+        message: String,
+    },
+    Regression {
+        path: PathBuf,
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn asset(path: &Path, message: impl fmt::Display) -> Self {
+        Error::Asset {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn video(message: impl fmt::Display) -> Self {
+        Error::Video(message.to_string())
+    }
+
+    pub fn input(message: impl fmt::Display) -> Self {
+        Error::Input(message.to_string())
+    }
+
+    pub fn config(path: Option<&Path>, message: impl fmt::Display) -> Self {
+        Error::Config {
+            path: path.map(Path::to_path_buf),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn simulation(path: Option<&Path>, message: impl fmt::Display) -> Self {
+        Error::Simulation {
+            path: path.map(Path::to_path_buf),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn regression(path: &Path, message: impl fmt::Display) -> Self {
+        Error::Regression {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::from(match self {
+            Error::Config { .. } => 2,
+            Error::Asset { .. } => 3,
+            Error::Video(_) => 4,
+            Error::Input(_) => 5,
+            Error::Simulation { .. } => 6,
+            Error::Regression { .. } => 7,
+        })
+    }
+
+    fn hint(&self) -> &'static str {
+        match self {
+            Error::Asset { .. } => {
+                "check that the file exists and is readable, or point --assets or --theme elsewhere"
+            }
+            Error::Video(_) => {
+                "check that a display is available, or try --renderer software"
+            }
+            Error::Input(_) => {
+                "reconnect the controller, or check that the recording was made by this version"
+            }
+            Error::Config { path: Some(_), .. } => {
+                "fix or delete the file, the defaults are used for anything it leaves out"
+            }
+            Error::Config { path: None, .. } => "run with --help to list the valid options",
+            Error::Simulation { .. } => {
+                "compare the file with the scenarios and subcommands sections of the readme"
+            }
+            Error::Regression { .. } => {
+                "compare the .actual.png frames with the references, and rerun golden with --bless if the change is intended"
+            }
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Asset { path, message } => {
+                write!(f, "asset error in {}: {message}", path.display())?;
+            }
+            Error::Video(message) => write!(f, "video error: {message}")?,
+            Error::Input(message) => write!(f, "input error: {message}")?,
+            Error::Config {
+                path: Some(path),
+                message,
+            } => write!(f, "config error in {}: {message}", path.display())?,
+            Error::Config {
+                path: None,
+                message,
+            } => write!(f, "config error: {message}")?,
+            Error::Simulation {
+                path: Some(path),
+                message,
+            } => write!(f, "simulation error in {}: {message}", path.display())?,
+            Error::Simulation {
+                path: None,
+                message,
+            } => write!(f, "simulation error: {message}")?,
+            Error::Regression { path, message } => {
+                write!(f, "regression in {}: {message}", path.display())?;
+            }
+        }
+        write!(f, "\n  hint: {}", self.hint())
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,114 @@
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use sdl2::render::WindowCanvas;
+use sdl2::video::Window;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::simulation::TICK_SECONDS;
+
+pub const DEFAULT_MAX_FPS: u32 = 60;
+const TICK: Duration = Duration::from_nanos((TICK_SECONDS * 1e9) as u64);
+const MAX_TICKS_PER_FRAME: u32 = 5;
+const SMOOTHING: f64 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Renderer {
+    Software,
+    Accelerated,
+}
+
+fn build(window: Window, renderer: Renderer, vsync: bool) -> Result<WindowCanvas, String> {
+    let builder = window.into_canvas();
+    let builder = match renderer {
+        Renderer::Software => builder.software(),
+        Renderer::Accelerated => builder.accelerated(),
+    };
+    let builder = if vsync {
+        builder.present_vsync()
+    } else {
+        builder
+    };
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Builds the canvas for `window`. Building consumes the window even when it
+/// fails, so falling back from an accelerated renderer to software rendering
+/// needs `recreate` to open a new one.
+pub fn canvas(
+    window: Window,
+    renderer: Renderer,
+    vsync: bool,
+    recreate: impl FnOnce() -> Result<Window, Error>,
+) -> Result<WindowCanvas, Error> {
+    let canvas = match build(window, renderer, vsync) {
+        Ok(canvas) => canvas,
+        Err(e) if renderer == Renderer::Accelerated => {
+            log::warn!("unable to create an accelerated renderer ({e}), falling back to software rendering");
+            build(recreate()?, Renderer::Software, vsync)
+                .map_err(|e| Error::video(format!("unable to create the renderer: {e}")))?
+        }
+        Err(e) => return Err(Error::video(format!("unable to create the renderer: {e}"))),
+    };
+    log::info!("using the {} renderer", canvas.info().name);
+    Ok(canvas)
+}
+
+pub struct Clock {
+    frame_interval: Option<Duration>,
+    deadline: Instant,
+    last_frame: Instant,
+    accumulator: Duration,
+    pub frame_time: Duration,
+}
+
+impl Clock {
+    pub fn new(max_fps: Option<u32>) -> Self {
+        let now = Instant::now();
+        Self {
+            frame_interval: max_fps
+                .filter(|fps| *fps > 0)
+                .map(|fps| Duration::from_secs(1) / fps),
+            deadline: now,
+            last_frame: now,
+            accumulator: Duration::ZERO,
+            frame_time: TICK,
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        1.0 / self.frame_time.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn wait(&mut self) {
+        if let Some(interval) = self.frame_interval {
+            self.deadline += interval;
+            let now = Instant::now();
+            if self.deadline > now {
+                std::thread::sleep(self.deadline - now);
+            } else {
+                self.deadline = now;
+            }
+        }
+
+        let now = Instant::now();
+        let elapsed = now - self.last_frame;
+        self.last_frame = now;
+
+        let smoothed =
+            self.frame_time.as_secs_f64() * (1.0 - SMOOTHING) + elapsed.as_secs_f64() * SMOOTHING;
+        self.frame_time = Duration::from_secs_f64(smoothed);
+        self.accumulator = (self.accumulator + elapsed).min(TICK * MAX_TICKS_PER_FRAME);
+    }
+
+    pub fn ticks(&mut self) -> u32 {
+        let mut ticks = 0;
+        while self.accumulator >= TICK {
+            self.accumulator -= TICK;
+            ticks += 1;
+        }
+        ticks
+    }
+}
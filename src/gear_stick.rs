@@ -1,8 +1,12 @@
+use serde::{Deserialize, Serialize};
+
 use crate::utils;
 
 pub const NORMALIZED_RPM: f64 = 208.78;
 pub const REAR_GEAR_RATIO: f64 = 3.23;
 pub const TIRE_DIAMETER: f64 = 26.5;
+pub const GEAR_DEPTH: f64 = 0.9;
+pub const CENTER_LANE: f64 = 0.25;
 
 pub struct GearStick {
     smooth_factor: f64,
@@ -58,29 +62,29 @@ impl GearStick {
         if is_clutched {
             return Gear::Neutral;
         }
-        if (-0.9..=0.9).contains(&y) {
+        if (-GEAR_DEPTH..=GEAR_DEPTH).contains(&y) {
             return Gear::Neutral;
         }
-        if (0.25..=0.9).contains(&x) {
+        if (CENTER_LANE..=GEAR_DEPTH).contains(&x) {
             return Gear::Neutral;
         }
-        if (-0.9..=-0.25).contains(&x) {
+        if (-GEAR_DEPTH..=-CENTER_LANE).contains(&x) {
             return Gear::Neutral;
         }
 
-        if x < -0.9 {
-            if y <= -0.9 {
+        if x < -GEAR_DEPTH {
+            if y <= -GEAR_DEPTH {
                 Gear::First
             } else {
                 Gear::Second
             }
-        } else if x < 0.25 {
-            if y <= -0.9 {
+        } else if x < CENTER_LANE {
+            if y <= -GEAR_DEPTH {
                 Gear::Third
             } else {
                 Gear::Fourth
             }
-        } else if y <= -0.9 {
+        } else if y <= -GEAR_DEPTH {
             Gear::Fifth
         } else {
             Gear::Rocket
@@ -103,7 +107,8 @@ impl Default for GearStick {
     }
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Gear {
     Neutral,
     First,
@@ -115,6 +120,31 @@ pub enum Gear {
 }
 
 impl Gear {
+    pub fn parse(name: &str) -> Option<Gear> {
+        match name {
+            "neutral" | "N" => Some(Gear::Neutral),
+            "first" | "1" => Some(Gear::First),
+            "second" | "2" => Some(Gear::Second),
+            "third" | "3" => Some(Gear::Third),
+            "fourth" | "4" => Some(Gear::Fourth),
+            "fifth" | "5" => Some(Gear::Fifth),
+            "rocket" | "R" => Some(Gear::Rocket),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Gear::Neutral => "N",
+            Gear::First => "1",
+            Gear::Second => "2",
+            Gear::Third => "3",
+            Gear::Fourth => "4",
+            Gear::Fifth => "5",
+            Gear::Rocket => "R",
+        }
+    }
+
     pub fn gear_ratio(&self) -> f64 {
         match self {
             Gear::First | Gear::Neutral => 3.55,
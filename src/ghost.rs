@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::draw;
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::simulation::TICK_SECONDS;
+use crate::storage;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Sample {
+    pub rpm: f64,
+    pub kmh: f64,
+    pub gear: Gear,
+    pub distance: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Ghost {
+    pub time: f64,
+    samples: Vec<Sample>,
+}
+
+impl Ghost {
+    pub fn new(time: f64, samples: &[Sample]) -> Option<Self> {
+        (!samples.is_empty()).then(|| Self {
+            time,
+            samples: samples.to_vec(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        let Some(ghost) = storage::load::<Self>(path, "ghost")? else {
+            return Ok(None);
+        };
+        if ghost.samples.is_empty() {
+            return Err(Error::config(Some(path), "ghost has no samples"));
+        }
+        Ok(Some(ghost))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        storage::save(path, "ghost", self)
+    }
+
+    fn time_at(&self, distance: f64) -> Option<f64> {
+        let index = self
+            .samples
+            .iter()
+            .position(|sample| sample.distance >= distance)?;
+        let Some(previous) = index.checked_sub(1).map(|index| self.samples[index]) else {
+            return Some(0.0);
+        };
+        let next = self.samples[index];
+        let fraction = if next.distance > previous.distance {
+            (distance - previous.distance) / (next.distance - previous.distance)
+        } else {
+            1.0
+        };
+        Some((index as f64 - 1.0 + fraction) * TICK_SECONDS)
+    }
+
+    pub fn replay(&self, trace: &[Sample]) -> Option<draw::Ghost> {
+        let live = trace.last()?;
+        let tick = trace.len() - 1;
+        let sample = self.samples.get(tick).or(self.samples.last())?;
+        let elapsed = tick as f64 * TICK_SECONDS;
+
+        Some(draw::Ghost {
+            rpm: sample.rpm,
+            gear: sample.gear,
+            delta: self.time_at(live.distance).map(|time| elapsed - time),
+        })
+    }
+}
@@ -1,9 +1,13 @@
 use crate::{
-    gear_stick::Gear,
+    gear_stick::{Gear, CENTER_LANE},
     input::Input,
     utils::{self, clamp_f64},
 };
 
+pub const CROSS_GATE: f64 = 0.5;
+pub const OUTER_GATE: f64 = 0.925;
+pub const CENTER_GATE: f64 = 0.24;
+
 pub struct Hand {
     smooth_factor: f64,
     pub offset: (f64, f64),
@@ -37,21 +41,21 @@ impl Hand {
 pub fn clamp_clutch_up(target: (f64, f64), old: (f64, f64), gear: &Gear) -> (f64, f64) {
     let (x_min, x_max) = match gear {
         Gear::Neutral => {
-            if target.1 > -0.5 && target.1 < 0.5 {
+            if target.1 > -CROSS_GATE && target.1 < CROSS_GATE {
                 (-1.0, 1.0)
-            } else if old.0 <= -0.925 {
-                (-1.0, -0.925)
-            } else if old.0 >= -0.25 && old.0 <= 0.25 {
-                (-0.24, 0.24)
-            } else if old.0 >= 0.925 {
-                (0.925, 1.0)
+            } else if old.0 <= -OUTER_GATE {
+                (-1.0, -OUTER_GATE)
+            } else if old.0 >= -CENTER_LANE && old.0 <= CENTER_LANE {
+                (-CENTER_GATE, CENTER_GATE)
+            } else if old.0 >= OUTER_GATE {
+                (OUTER_GATE, 1.0)
             } else {
                 (-1.0, 1.0)
             }
         }
-        Gear::First | Gear::Second => (-1.0, -0.925),
-        Gear::Third | Gear::Fourth => (-0.24, 0.24),
-        Gear::Fifth | Gear::Rocket => (0.925, 1.0),
+        Gear::First | Gear::Second => (-1.0, -OUTER_GATE),
+        Gear::Third | Gear::Fourth => (-CENTER_GATE, CENTER_GATE),
+        Gear::Fifth | Gear::Rocket => (OUTER_GATE, 1.0),
     };
 
     let (y_min, y_max) = match gear {
@@ -0,0 +1,235 @@
+use std::path::Path;
+
+use chrono::NaiveTime;
+use sdl2::image::LoadSurface;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::{Canvas, RenderTarget};
+use sdl2::surface::Surface;
+
+use crate::assets;
+use crate::atlas::Sprites;
+use crate::capture::save_png;
+use crate::cli::GoldenArgs;
+use crate::cluster::Cluster;
+use crate::draw;
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::layout::{DESIGN_HEIGHT, DESIGN_WIDTH};
+use crate::player::PedalTravel;
+use crate::scene;
+use crate::settings::Settings;
+use crate::theme::Theme;
+use crate::units::Units;
+
+const CHANNEL_TOLERANCE: u8 = 2;
+
+pub struct State {
+    pub name: &'static str,
+    pub cluster: Cluster,
+    pub gear: Gear,
+    pub rpm: f64,
+    pub kmh: f64,
+    pub stick: (f64, f64),
+    pub pedals: PedalTravel,
+}
+
+fn state(name: &'static str, gear: Gear, rpm: f64, kmh: f64, stick: (f64, f64)) -> State {
+    State {
+        name,
+        cluster: Cluster::Analog,
+        gear,
+        rpm,
+        kmh,
+        stick,
+        pedals: PedalTravel::default(),
+    }
+}
+
+pub fn golden_states() -> Vec<State> {
+    vec![
+        state("neutral-idle", Gear::Neutral, 700.0, 0.0, (0.0, 0.0)),
+        state("first", Gear::First, 2500.0, 15.0, (-1.0, -1.0)),
+        state("second", Gear::Second, 3500.0, 40.0, (-1.0, 1.0)),
+        state("third", Gear::Third, 4000.0, 70.0, (0.0, -1.0)),
+        state("fourth", Gear::Fourth, 4500.0, 100.0, (0.0, 1.0)),
+        state("fifth", Gear::Fifth, 5000.0, 140.0, (1.0, -1.0)),
+        state("rocket", Gear::Rocket, 1500.0, 8.0, (1.0, 1.0)),
+        State {
+            pedals: PedalTravel {
+                clutch: 1.0,
+                brake: 0.5,
+                throttle: 0.25,
+            },
+            ..state("pedals", Gear::Neutral, 2000.0, 30.0, (0.0, 0.0))
+        },
+        State {
+            cluster: Cluster::Digital,
+            ..state("digital-cruise", Gear::Fourth, 3000.0, 90.0, (0.0, 1.0))
+        },
+        State {
+            cluster: Cluster::Digital,
+            ..state("digital-redline", Gear::Second, 7200.0, 80.0, (-1.0, 1.0))
+        },
+    ]
+}
+
+pub fn render<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    state: &State,
+) -> Result<(), Error> {
+    let layout = draw::pane(
+        canvas,
+        (DESIGN_WIDTH as i16, DESIGN_HEIGHT as i16),
+        0,
+        1,
+        Some(1.0),
+    )?;
+
+    canvas.set_draw_color(theme.colors.background);
+    canvas.clear();
+    scene::road(canvas, &theme.road, &layout, 0.0, (0, 0))?;
+
+    draw::all(
+        canvas,
+        sprites,
+        theme,
+        &draw::Options {
+            cluster: state.cluster,
+            units: Units::Metric,
+        },
+        &layout,
+        &draw::Peripherals {
+            rpm: state.rpm,
+            kmh: state.kmh,
+            gear: &state.gear,
+            odometer: 1234.5,
+            trip: 12.3,
+            time: NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default(),
+            ghost: None,
+        },
+        state.stick,
+        &draw::Hand {
+            offset: state.stick,
+            grabbing: state.stick != (0.0, 0.0),
+        },
+        &draw::Pedals {
+            travel: state.pedals,
+            trace: None,
+        },
+    )?;
+
+    draw::reset_pane(canvas)
+}
+
+fn pixels(surface: &Surface) -> Result<Vec<Vec<u8>>, String> {
+    let surface = surface.convert_format(PixelFormatEnum::RGB24)?;
+    let (width, pitch) = (surface.width() as usize * 3, surface.pitch() as usize);
+    Ok(surface.with_lock(|data| {
+        data.chunks(pitch)
+            .map(|row| row[..width].to_vec())
+            .collect()
+    }))
+}
+
+fn differing_pixels(actual: &[Vec<u8>], expected: &[Vec<u8>]) -> usize {
+    actual
+        .iter()
+        .zip(expected)
+        .map(|(actual, expected)| {
+            actual
+                .chunks(3)
+                .zip(expected.chunks(3))
+                .filter(|(a, b)| {
+                    a.iter()
+                        .zip(b.iter())
+                        .any(|(a, b)| a.abs_diff(*b) > CHANNEL_TOLERANCE)
+                })
+                .count()
+        })
+        .sum()
+}
+
+pub fn compare<T: RenderTarget>(canvas: &Canvas<T>, reference: &Path) -> Result<usize, Error> {
+    let (width, height) = canvas.output_size().map_err(Error::video)?;
+    let mut data = canvas
+        .read_pixels(None, PixelFormatEnum::RGB24)
+        .map_err(Error::video)?;
+    let actual = Surface::from_data(&mut data, width, height, width * 3, PixelFormatEnum::RGB24)
+        .map_err(Error::video)?;
+    let expected = Surface::from_file(reference)
+        .map_err(|e| Error::asset(reference, format!("unable to load reference: {e}")))?;
+
+    if expected.size() != actual.size() {
+        return Err(Error::regression(
+            reference,
+            format!(
+                "reference is {:?}, rendered frame is {:?}",
+                expected.size(),
+                actual.size()
+            ),
+        ));
+    }
+
+    let actual = pixels(&actual).map_err(Error::video)?;
+    let expected = pixels(&expected).map_err(|e| Error::asset(reference, e))?;
+    Ok(differing_pixels(&actual, &expected))
+}
+
+pub fn run(args: &GoldenArgs) -> Result<(), Error> {
+    let assets = assets::Assets::new(args.assets.as_deref());
+    let directory = assets.theme(&Settings::default().theme, false)?;
+    let (theme, atlas) = assets::load_theme(&directory)?;
+    let surface = Surface::new(
+        DESIGN_WIDTH as u32,
+        DESIGN_HEIGHT as u32,
+        PixelFormatEnum::RGB888,
+    )
+    .map_err(Error::video)?;
+    let mut canvas = surface.into_canvas().map_err(Error::video)?;
+    let texture_creator = canvas.texture_creator();
+    let sprites = Sprites::load(&texture_creator, atlas)?;
+
+    std::fs::create_dir_all(&args.directory)
+        .map_err(|e| Error::asset(&args.directory, format!("unable to create directory: {e}")))?;
+
+    let mut failures = Vec::new();
+    for state in golden_states() {
+        render(&mut canvas, &sprites, &theme, &state)?;
+        let reference = args.directory.join(format!("{}.png", state.name));
+
+        if args.bless {
+            save_png(&canvas, &reference)?;
+            log::info!("wrote {}", reference.display());
+            continue;
+        }
+
+        match compare(&canvas, &reference) {
+            Ok(0) => log::info!("{}: ok", state.name),
+            Ok(count) => {
+                let actual = args.directory.join(format!("{}.actual.png", state.name));
+                save_png(&canvas, &actual)?;
+                log::error!(
+                    "{}: {count} pixels differ, see {}",
+                    state.name,
+                    actual.display()
+                );
+                failures.push(state.name);
+            }
+            Err(err) => {
+                log::error!("{}: {err}", state.name);
+                failures.push(state.name);
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::regression(
+            &args.directory,
+            format!("golden images differ: {}", failures.join(", ")),
+        ))
+    }
+}
@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use sdl2::controller::GameController;
+use sdl2::keyboard::Keycode;
+use serde::{Deserialize, Serialize};
 
+use crate::error::Error;
 use crate::utils::clamp_f64;
 
 #[derive(Debug)]
@@ -12,49 +15,217 @@ pub enum ActionState {
     JustInactive,
 }
 
-#[derive(Hash, Eq, PartialEq)]
+#[derive(Hash, Eq, PartialEq, Ord, PartialOrd, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Action {
     Grab,
     Accelerate,
     Brake,
     Clutch,
+    ResetTrip,
+    DebugOverlay,
+    Telemetry,
+    MoveTelemetry,
+    Screenshot,
+    Menu,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
     Quit,
 }
 
-impl TryFrom<sdl2::keyboard::Keycode> for Action {
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::Grab,
+        Action::Accelerate,
+        Action::Brake,
+        Action::Clutch,
+        Action::ResetTrip,
+        Action::DebugOverlay,
+        Action::Telemetry,
+        Action::MoveTelemetry,
+        Action::Screenshot,
+        Action::Menu,
+        Action::MenuUp,
+        Action::MenuDown,
+        Action::MenuLeft,
+        Action::MenuRight,
+        Action::Confirm,
+        Action::Quit,
+    ];
+}
+
+impl Action {
+    pub const REBINDABLE: [Action; 9] = [
+        Action::Grab,
+        Action::Accelerate,
+        Action::Brake,
+        Action::Clutch,
+        Action::ResetTrip,
+        Action::DebugOverlay,
+        Action::Telemetry,
+        Action::MoveTelemetry,
+        Action::Screenshot,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Grab => "grab",
+            Action::Accelerate => "accelerate",
+            Action::Brake => "brake",
+            Action::Clutch => "clutch",
+            Action::ResetTrip => "reset trip",
+            Action::DebugOverlay => "debug overlay",
+            Action::Telemetry => "telemetry",
+            Action::MoveTelemetry => "move telemetry",
+            Action::Screenshot => "screenshot",
+            Action::Menu => "menu",
+            Action::MenuUp => "menu up",
+            Action::MenuDown => "menu down",
+            Action::MenuLeft => "menu left",
+            Action::MenuRight => "menu right",
+            Action::Confirm => "confirm",
+            Action::Quit => "quit",
+        }
+    }
+}
+
+/// Keys that keep their meaning whatever is rebound, so the menu can always
+/// be driven.
+const RESERVED_KEYS: [Keycode; 6] = [
+    Keycode::Up,
+    Keycode::Down,
+    Keycode::Left,
+    Keycode::Right,
+    Keycode::Return,
+    Keycode::Escape,
+];
+
+/// The keyboard bindings of [`Action::REBINDABLE`], stored in the settings
+/// file as a `[keys]` table of action to SDL key name.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    try_from = "BTreeMap<Action, String>",
+    into = "BTreeMap<Action, String>"
+)]
+pub struct Keys(HashMap<Action, Keycode>);
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (Action::Grab, Keycode::Space),
+            (Action::Accelerate, Keycode::W),
+            (Action::Brake, Keycode::S),
+            (Action::Clutch, Keycode::LShift),
+            (Action::ResetTrip, Keycode::T),
+            (Action::DebugOverlay, Keycode::F3),
+            (Action::Telemetry, Keycode::F4),
+            (Action::MoveTelemetry, Keycode::F5),
+            (Action::Screenshot, Keycode::F12),
+        ]))
+    }
+}
+
+impl TryFrom<BTreeMap<Action, String>> for Keys {
     type Error = String;
 
-    fn try_from(value: sdl2::keyboard::Keycode) -> Result<Self, Self::Error> {
-        match value {
-            sdl2::keyboard::Keycode::W | sdl2::keyboard::Keycode::Up => Ok(Action::Accelerate),
-            sdl2::keyboard::Keycode::S | sdl2::keyboard::Keycode::Down => Ok(Action::Brake),
-            sdl2::keyboard::Keycode::Space => Ok(Action::Grab),
-            sdl2::keyboard::Keycode::LShift => Ok(Action::Clutch),
-            sdl2::keyboard::Keycode::Escape => Ok(Action::Quit),
-            key => Err(format!("unrecognized keycode: {key:#?}")),
+    fn try_from(names: BTreeMap<Action, String>) -> Result<Self, Self::Error> {
+        let mut keys = Self::default();
+        for (action, name) in names {
+            if !Action::REBINDABLE.contains(&action) {
+                return Err(format!("{} cannot be rebound", action.name()));
+            }
+            let key = Keycode::from_name(&name).ok_or_else(|| format!("unknown key `{name}`"))?;
+            if !keys.bind(action, key) {
+                return Err(format!("`{name}` is reserved for the menu"));
+            }
         }
+        Ok(keys)
+    }
+}
+
+impl From<Keys> for BTreeMap<Action, String> {
+    fn from(keys: Keys) -> Self {
+        keys.0
+            .into_iter()
+            .map(|(action, key)| (action, key.name()))
+            .collect()
+    }
+}
+
+impl Keys {
+    pub fn key(&self, action: Action) -> Option<Keycode> {
+        self.0.get(&action).copied()
+    }
+
+    /// Binds `key` to `action`, swapping bindings with whichever action had
+    /// `key` before. Returns false for the reserved menu keys.
+    pub fn bind(&mut self, action: Action, key: Keycode) -> bool {
+        if RESERVED_KEYS.contains(&key) {
+            return false;
+        }
+
+        let previous = self.0.insert(action, key);
+        let clash = self
+            .0
+            .iter()
+            .find(|(other, bound)| **other != action && **bound == key)
+            .map(|(other, _)| *other);
+        if let Some(other) = clash {
+            match previous {
+                Some(previous) => self.0.insert(other, previous),
+                None => self.0.remove(&other),
+            };
+        }
+        true
+    }
+
+    pub fn action(&self, key: Keycode) -> Option<Action> {
+        let bound = self
+            .0
+            .iter()
+            .find(|(_, bound)| **bound == key)
+            .map(|(action, _)| *action);
+        bound.or(match key {
+            Keycode::Up => Some(Action::Accelerate),
+            Keycode::Down => Some(Action::Brake),
+            Keycode::A | Keycode::Left => Some(Action::MenuLeft),
+            Keycode::D | Keycode::Right => Some(Action::MenuRight),
+            Keycode::Return => Some(Action::Confirm),
+            Keycode::Escape => Some(Action::Menu),
+            _ => None,
+        })
     }
 }
 
 impl TryFrom<sdl2::controller::Button> for Action {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(value: sdl2::controller::Button) -> Result<Self, Self::Error> {
         match value {
             sdl2::controller::Button::RightShoulder => Ok(Action::Grab),
             sdl2::controller::Button::LeftShoulder => Ok(Action::Clutch),
-            key => Err(format!("unrecognized keycode: {key:#?}")),
+            sdl2::controller::Button::Back => Ok(Action::ResetTrip),
+            sdl2::controller::Button::Start | sdl2::controller::Button::B => Ok(Action::Menu),
+            sdl2::controller::Button::DPadUp => Ok(Action::MenuUp),
+            sdl2::controller::Button::DPadDown => Ok(Action::MenuDown),
+            sdl2::controller::Button::DPadLeft => Ok(Action::MenuLeft),
+            sdl2::controller::Button::DPadRight => Ok(Action::MenuRight),
+            sdl2::controller::Button::A => Ok(Action::Confirm),
+            key => Err(Error::input(format!("unbound controller button {key:?}"))),
         }
     }
 }
 
 impl TryFrom<sdl2::mouse::MouseButton> for Action {
-    type Error = String;
+    type Error = Error;
 
     fn try_from(value: sdl2::mouse::MouseButton) -> Result<Self, Self::Error> {
         match value {
             sdl2::mouse::MouseButton::Left => Ok(Action::Grab),
-            key => Err(format!("unrecognized keycode: {key:#?}")),
+            key => Err(Error::input(format!("unbound mouse button {key:?}"))),
         }
     }
 }
@@ -64,8 +235,11 @@ pub struct Input {
     mouse_sensitivity: f64,
     pub brake_alpha: f64,
     pub speeder_alpha: f64,
+    pub clutch_alpha: f64,
     pub hand: (f64, f64),
-    pub active_controller: Option<GameController>,
+    pub pressed_key: Option<Keycode>,
+    pub keyboard: bool,
+    pub controller: Option<GameController>,
 }
 
 impl Input {
@@ -73,15 +247,40 @@ impl Input {
         Self {
             action_map: HashMap::new(),
             hand: (0.0, 0.0),
+            pressed_key: None,
             mouse_sensitivity,
             brake_alpha: 1.0,
             speeder_alpha: 1.0,
-            active_controller: None,
+            clutch_alpha: 1.0,
+            keyboard: true,
+            controller: None,
+        }
+    }
+
+    pub fn set_sensitivity(&mut self, mouse_sensitivity: f64) {
+        self.mouse_sensitivity = mouse_sensitivity;
+    }
+
+    pub fn with_controller(controller: GameController) -> Self {
+        Self {
+            action_map: HashMap::new(),
+            hand: (0.0, 0.0),
+            pressed_key: None,
+            mouse_sensitivity: 128.0,
+            brake_alpha: 1.0,
+            speeder_alpha: 1.0,
+            clutch_alpha: 1.0,
+            keyboard: false,
+            controller: Some(controller),
         }
     }
 
+    pub fn controller_id(&self) -> Option<u32> {
+        self.controller.as_ref().map(GameController::instance_id)
+    }
+
     pub fn shake_controller(&mut self) {
-        if let Some(controller) = &mut self.active_controller {
+        if let Some(controller) = &mut self.controller {
             if let Err(err) = controller.set_rumble(u16::MAX, u16::MAX, 500) {
                 log::warn!("unable to rumble: {err}");
             }
@@ -144,6 +343,17 @@ impl Input {
         )
     }
 
+    pub fn action_pressed(&self, action: &Action) -> bool {
+        self.action_changed(action) && self.action_active(action)
+    }
+
+    pub fn tick(&mut self) {
+        self.pressed_key = None;
+        for action in Action::ALL {
+            self.action_tick(action);
+        }
+    }
+
     pub fn action_tick(&mut self, action: Action) {
         let state = match self.get(&action) {
             Some(ActionState::Inactive | ActionState::JustInactive) | None => ActionState::Inactive,
@@ -154,9 +364,9 @@ impl Input {
 
     pub fn key_down<A: TryInto<Action> + std::fmt::Debug + Copy>(&mut self, action: A) {
         let Ok(action) = action.try_into() else {
-        log::debug!("unrecognized action {action:#?}");
-        return;
-    };
+            log::debug!("unrecognized action {action:#?}");
+            return;
+        };
         let state = match self.get(&action) {
             Some(ActionState::Inactive | ActionState::JustInactive) | None => {
                 ActionState::JustActive
@@ -168,9 +378,9 @@ impl Input {
 
     pub fn key_up<A: TryInto<Action> + std::fmt::Debug + Copy>(&mut self, action: A) {
         let Ok(action) = action.try_into() else {
-        log::debug!("unrecognized key {action:#?}");
-        return;
-    };
+            log::debug!("unrecognized key {action:#?}");
+            return;
+        };
         let state = match self.get(&action) {
             Some(ActionState::Active | ActionState::JustActive) | None => ActionState::JustInactive,
             Some(ActionState::Inactive | ActionState::JustInactive) => ActionState::Inactive,
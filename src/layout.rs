@@ -0,0 +1,60 @@
+pub const DESIGN_WIDTH: f64 = 1920.0;
+pub const DESIGN_HEIGHT: f64 = 800.0;
+
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Center,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+pub struct Layout {
+    pub size: (i16, i16),
+}
+
+pub fn ui_scale(size: (i16, i16)) -> f64 {
+    let x = f64::from(size.0) / DESIGN_WIDTH;
+    let y = f64::from(size.1) / DESIGN_HEIGHT;
+    x.min(y).max(0.1)
+}
+
+impl Layout {
+    pub fn new(size: (i16, i16), scale: f64) -> Result<Self, String> {
+        if scale <= 0.0 {
+            return Err(format!("invalid ui scale {scale}"));
+        }
+
+        let scaled = |value: i16| {
+            let value = (f64::from(value) / scale).round();
+            if value > f64::from(i16::MAX) {
+                Err(format!("ui scale {scale} too small for a size of {size:?}"))
+            } else {
+                Ok(value as i16)
+            }
+        };
+
+        Ok(Self {
+            size: (scaled(size.0)?, scaled(size.1)?),
+        })
+    }
+
+    pub fn anchor(&self, anchor: &Anchor, offset: (i16, i16)) -> (i16, i16) {
+        let (width, height) = self.size;
+
+        let x = match anchor {
+            Anchor::TopLeft | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => width / 2,
+            Anchor::TopRight | Anchor::BottomRight => width,
+        };
+        let y = match anchor {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Center => height / 2,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => height,
+        };
+
+        (x + offset.0, y + offset.1)
+    }
+}
@@ -1,117 +1,177 @@
 #![warn(clippy::unwrap_used)]
 #![allow(clippy::cast_possible_truncation)]
 
+mod assets;
+mod atlas;
+mod capture;
 mod cli;
+mod cluster;
+mod debug;
+mod drag;
 mod draw;
+mod drivetrain;
+mod error;
+mod frame;
 mod gear_stick;
+mod ghost;
 mod hand;
+mod headless;
 mod input;
+mod layout;
+mod menu;
+mod player;
+mod recording;
+mod scene;
+mod scoring;
+mod script;
+mod settings;
+mod simulation;
+mod storage;
+mod telemetry;
+mod text;
+mod theme;
+mod timing;
+mod tutorial;
+mod units;
 mod utils;
-
-use cli::{Cli, Parser};
-use gear_stick::{expected_kmh, expected_rpm, Gear, GearStick};
-use hand::{clamp_clutch_down, clamp_clutch_up, Hand};
-use input::{Action, Input};
-use sdl2::controller::Axis;
-use sdl2::event::Event;
-use sdl2::image::{InitFlag, LoadTexture};
-use sdl2::keyboard::Keycode;
-use sdl2::pixels::Color;
-use sdl2::render::WindowCanvas;
+mod vehicle;
+
+use atlas::Sprites;
+use chrono::Local;
+use clap::Parser;
+use cli::{Cli, Command, PlayArgs};
+use drag::Race;
+use error::Error;
+use gear_stick::Gear;
+use ghost::Ghost;
+use input::{Action, Input, Keys};
+use player::Player;
+use recording::Recording;
+use scene::Scene;
+use sdl2::controller::{Axis, GameController};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::image::InitFlag;
 use sdl2::video::Window;
 use sdl2::{GameControllerSubsystem, Sdl};
+use std::collections::HashMap;
 use std::path::Path;
-use std::time::Duration;
-use utils::lerp_1d;
+use std::process::ExitCode;
+use std::time::Instant;
+use theme::Theme;
+use timing::{Benchmark, Records};
+use tutorial::Tutorial;
 
-fn prepare_window(sdl_context: &Sdl, fullscreen: bool) -> Result<Window, String> {
-    let video_subsystem = sdl_context.video()?;
-    let _image_context = sdl2::image::init(InitFlag::PNG | InitFlag::JPG)?;
+fn prepare_window(
+    sdl_context: &Sdl,
+    fullscreen: bool,
+    (width, height): (u32, u32),
+) -> Result<Window, Error> {
+    let video_subsystem = sdl_context.video().map_err(Error::video)?;
+    let _image_context = sdl2::image::init(InitFlag::PNG | InitFlag::JPG)
+        .map_err(|e| Error::video(format!("unable to initialise SDL_image: {e}")))?;
     let mut window = video_subsystem
-        .window("car-demo", 1920, 800)
+        .window("car-demo", width, height)
         .position_centered()
+        .resizable()
         .build()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| Error::video(format!("unable to create a {width}x{height} window: {e}")))?;
     if fullscreen {
-        window.set_fullscreen(sdl2::video::FullscreenType::Desktop)?;
+        window
+            .set_fullscreen(sdl2::video::FullscreenType::Desktop)
+            .map_err(Error::video)?;
     }
     Ok(window)
 }
 
-fn prepare_canvas(window: Window) -> Result<WindowCanvas, String> {
-    window
-        .into_canvas()
-        .software()
-        .build()
-        .map_err(|e| e.to_string())
-}
+fn check_for_controllers(
+    pool: &mut HashMap<u32, GameController>,
+    system: &GameControllerSubsystem,
+) -> Result<(), Error> {
+    let joysticks = system.num_joysticks().map_err(Error::input)?;
+    if joysticks == 0 {
+        return Err(Error::input("no controllers connected"));
+    }
 
-fn flywheel_rpm(rpm: f64, input: &mut Input, neutral_gear: bool) -> f64 {
-    let speeder_down = input.action_active(&Action::Accelerate);
-    let speeder_alpha = input.speeder_alpha;
+    for index in (0..joysticks).filter(|index| system.is_game_controller(*index)) {
+        let controller = system
+            .open(index)
+            .map_err(|e| Error::input(format!("unable to open controller {index}: {e}")))?;
+        pool.insert(controller.instance_id(), controller);
+    }
+    Ok(())
+}
 
-    let min_rpm = 700.0;
-    let max_rpm = 8000.0;
+fn keyboard_player(players: &mut [Player]) -> Option<&mut Player> {
+    players.iter_mut().find(|player| player.input.keyboard)
+}
 
-    let rpm_to_accel = |rpm: f64| -rpm.powf(1.3) + 1.8 * rpm + 1.0;
-    let rpm_to_deaccel = |rpm: f64| rpm.powf(1.1) + 1.0;
+fn controller_player(players: &mut [Player], which: u32) -> Option<&mut Player> {
+    players
+        .iter_mut()
+        .find(|player| player.input.controller_id() == Some(which))
+}
 
-    let acceleration_rate = rpm_to_accel(rpm / 1000.0);
-    let deacceleration_rate = if neutral_gear {
-        rpm_to_deaccel(rpm / 1000.0)
-    } else {
-        1.0
+fn join(
+    players: &mut Vec<Player>,
+    pool: &mut HashMap<u32, GameController>,
+    which: u32,
+    benchmarks: &[Benchmark],
+) {
+    let Some(controller) = pool.remove(&which) else {
+        log::debug!("button press from unknown controller {which}");
+        return;
     };
 
-    let rpm = if speeder_down {
-        rpm + (1500.0 / 60.0) * acceleration_rate * speeder_alpha
-    } else {
-        rpm - 500.0 / 60.0 * deacceleration_rate
-    };
+    let abandoned = players
+        .iter_mut()
+        .enumerate()
+        .find(|(_, player)| !player.input.keyboard && player.input.controller.is_none());
 
-    if min_rpm > rpm {
-        min_rpm
-    } else if rpm > max_rpm {
-        input.shake_controller();
-        max_rpm - 100.0
+    if let Some((index, player)) = abandoned {
+        player.input.controller = Some(controller);
+        log::info!("controller {which} rejoined as player {}", index + 1);
     } else {
-        rpm
+        players.push(Player::new(Input::with_controller(controller)).with_benchmarks(benchmarks));
+        log::info!("controller {which} joined as player {}", players.len());
     }
 }
 
-fn check_for_controllers(
-    input: &mut Input,
-    system: &GameControllerSubsystem,
-) -> Result<(), String> {
-    if system.num_joysticks()? == 0 {
-        return Err("no controllers connected".to_string());
-    }
-
-    let controller = system.open(0).map_err(|e| e.to_string())?;
-    input.active_controller = Some(controller);
-    Ok(())
-}
-
+#[allow(clippy::too_many_lines)]
 fn poll_events(
     sdl_context: &Sdl,
-    input: &mut Input,
+    players: &mut Vec<Player>,
+    pool: &mut HashMap<u32, GameController>,
     controllers: &GameControllerSubsystem,
-) -> Result<(), String> {
-    for event in sdl_context.event_pump()?.poll_iter() {
+    window_size: &mut (i16, i16),
+    benchmarks: &[Benchmark],
+    keys: &Keys,
+) -> Result<(), Error> {
+    for event in sdl_context.event_pump().map_err(Error::input)?.poll_iter() {
         match event {
-            Event::Quit { .. }
-            | Event::KeyDown {
-                keycode: Some(Keycode::Escape),
-                ..
-            } => input.key_down(Keycode::Escape),
+            Event::Quit { .. } => {
+                if let Some(player) = keyboard_player(players) {
+                    player.input.key_down(Action::Quit);
+                }
+            }
             Event::KeyDown {
                 keycode: Some(key), ..
-            } => input.key_down(key),
+            } => {
+                if let Some(player) = keyboard_player(players) {
+                    player.input.pressed_key = Some(key);
+                    match keys.action(key) {
+                        Some(action) => player.input.key_down(action),
+                        None => log::debug!("unbound key {key:?}"),
+                    }
+                }
+            }
             Event::ControllerButtonDown {
                 timestamp: _,
-                which: _,
+                which,
                 button,
-            } => input.key_down(button),
+            } => match controller_player(players, which) {
+                Some(player) => player.input.key_down(button),
+                None => join(players, pool, which, benchmarks),
+            },
             Event::MouseButtonDown {
                 timestamp: _,
                 window_id: _,
@@ -120,15 +180,27 @@ fn poll_events(
                 clicks: _,
                 x: _,
                 y: _,
-            } => input.key_down(mouse_btn),
+            } => {
+                if let Some(player) = keyboard_player(players) {
+                    player.input.key_down(mouse_btn);
+                }
+            }
             Event::KeyUp {
                 keycode: Some(key), ..
-            } => input.key_up(key),
+            } => {
+                if let (Some(player), Some(action)) = (keyboard_player(players), keys.action(key)) {
+                    player.input.key_up(action);
+                }
+            }
             Event::ControllerButtonUp {
                 timestamp: _,
-                which: _,
+                which,
                 button,
-            } => input.key_up(button),
+            } => {
+                if let Some(player) = controller_player(players, which) {
+                    player.input.key_up(button);
+                }
+            }
             Event::MouseButtonUp {
                 timestamp: _,
                 window_id: _,
@@ -137,33 +209,43 @@ fn poll_events(
                 clicks: _,
                 x: _,
                 y: _,
-            } => input.key_up(mouse_btn),
+            } => {
+                if let Some(player) = keyboard_player(players) {
+                    player.input.key_up(mouse_btn);
+                }
+            }
             Event::ControllerAxisMotion {
                 timestamp: _,
-                which: _,
+                which,
                 axis,
                 value,
-            } => match axis {
-                Axis::RightX => input.update_hand_from_raw_x(value),
-                Axis::RightY => input.update_hand_from_raw_y(value),
-                Axis::TriggerLeft => {
-                    if value < 100 {
-                        input.key_up(Keycode::Down);
-                    } else {
-                        input.key_down(Keycode::Down);
+            } => {
+                let Some(player) = controller_player(players, which) else {
+                    continue;
+                };
+                let input = &mut player.input;
+                match axis {
+                    Axis::RightX => input.update_hand_from_raw_x(value),
+                    Axis::RightY => input.update_hand_from_raw_y(value),
+                    Axis::TriggerLeft => {
+                        if value < 100 {
+                            input.key_up(Action::Brake);
+                        } else {
+                            input.key_down(Action::Brake);
+                        }
+                        input.brake_alpha = f64::from(value) / f64::from(i16::MAX);
                     }
-                    input.brake_alpha = f64::from(value) / f64::from(i16::MAX);
-                }
-                Axis::TriggerRight => {
-                    if value < 100 {
-                        input.key_up(Keycode::Up);
-                    } else {
-                        input.key_down(Keycode::Up);
+                    Axis::TriggerRight => {
+                        if value < 100 {
+                            input.key_up(Action::Accelerate);
+                        } else {
+                            input.key_down(Action::Accelerate);
+                        }
+                        input.speeder_alpha = f64::from(value) / f64::from(i16::MAX);
                     }
-                    input.speeder_alpha = f64::from(value) / f64::from(i16::MAX);
+                    _ => {}
                 }
-                _ => {}
-            },
+            }
             Event::MouseMotion {
                 timestamp: _,
                 window_id: _,
@@ -173,18 +255,45 @@ fn poll_events(
                 y: _,
                 xrel,
                 yrel,
-            } => input.update_hand_relatively(xrel, yrel),
+            } => {
+                if let Some(player) = keyboard_player(players) {
+                    player.input.update_hand_relatively(xrel, yrel);
+                }
+            }
             Event::ControllerDeviceAdded {
                 timestamp: _,
                 which,
-            } => match controllers.open(which).map_err(|e| e.to_string()) {
-                Ok(controller) => input.active_controller = Some(controller),
+            } => match controllers.open(which) {
+                Ok(controller) => {
+                    let id = controller.instance_id();
+                    if controller_player(players, id).is_none() {
+                        pool.insert(id, controller);
+                    }
+                }
                 Err(err) => log::error!("unable to connect controller: {err}"),
             },
             Event::ControllerDeviceRemoved {
                 timestamp: _,
-                which: _,
-            } => input.active_controller = None,
+                which,
+            } => {
+                pool.remove(&which);
+                if let Some(player) = controller_player(players, which) {
+                    player.input.controller = None;
+                    log::info!("controller {which} disconnected");
+                }
+            }
+            Event::Window {
+                timestamp: _,
+                window_id: _,
+                win_event:
+                    WindowEvent::Resized(width, height) | WindowEvent::SizeChanged(width, height),
+            } => {
+                *window_size = (
+                    i16::try_from(width).map_err(Error::video)?,
+                    i16::try_from(height).map_err(Error::video)?,
+                );
+                log::debug!("window resized to {width}x{height}");
+            }
             e => log::debug!("unrecognized event {e:?}"),
         }
     }
@@ -192,207 +301,469 @@ fn poll_events(
     Ok(())
 }
 
-struct ClutchCooldown {
-    active: bool,
-    start_rpm: f64,
-    timer: f64,
-}
-
-impl Default for ClutchCooldown {
-    fn default() -> Self {
-        Self {
-            start_rpm: 0.0,
-            timer: 0.0,
-            active: false,
-        }
-    }
-}
-
-fn window_size(window: &Window) -> Result<(i16, i16), String> {
+fn window_size(window: &Window) -> Result<(i16, i16), Error> {
     let (width, height) = window.size();
     let size = (
-        i16::try_from(width).map_err(|e| e.to_string())?,
-        i16::try_from(height).map_err(|e| e.to_string())?,
+        i16::try_from(width).map_err(Error::video)?,
+        i16::try_from(height).map_err(Error::video)?,
     );
 
     Ok(size)
 }
 
-pub struct KmhRpmPair {
-    kmh: f64,
-    rpm: f64,
-}
-
-fn brake(kmh: f64, rpm: f64, gear: &Gear, brake_alpha: f64) -> KmhRpmPair {
-    let kmh_diff = (50.0 / 60.0) * brake_alpha;
-
-    let rpm_diff = if gear == &Gear::Neutral {
-        0.0
-    } else {
-        let rpm_before = expected_rpm(kmh, gear.gear_ratio());
-        let rpm_after = expected_rpm(kmh - kmh_diff, gear.gear_ratio());
-        rpm_before - rpm_after
-    };
-
-    KmhRpmPair {
-        kmh: kmh - kmh_diff,
-        rpm: rpm - rpm_diff,
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(err) = simple_logger::SimpleLogger::new()
+        .with_level(cli.log_level)
+        .init()
+    {
+        eprintln!("unable to start logging: {err}");
     }
-}
 
-fn switch_into_gear(
-    kmh: f64,
-    clutch_cooldown: &ClutchCooldown,
-    input: &mut Input,
-    gear: &Gear,
-) -> KmhRpmPair {
-    let target = expected_rpm(kmh, gear.gear_ratio());
-    let rpm = lerp_1d(clutch_cooldown.timer, clutch_cooldown.start_rpm, target);
-
-    if (rpm - target).abs() > 500.0 {
-        input.shake_controller();
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            err.exit_code()
+        }
     }
-
-    KmhRpmPair { kmh, rpm }
 }
 
-fn update_rpm_in_neutral(kmh: f64, rpm: f64, input: &mut Input) -> KmhRpmPair {
-    let rpm = flywheel_rpm(rpm, input, true);
-    let kmh = kmh - (1.0 / 60.0);
-    let min = expected_kmh(700.0, Gear::Neutral.gear_ratio());
-    let kmh = if kmh < min { min } else { kmh };
-
-    KmhRpmPair { kmh, rpm }
+fn run(cli: &Cli) -> Result<(), Error> {
+    match &cli.command {
+        None => play(cli, &cli.play, None),
+        Some(Command::Play(args)) => play(cli, args, None),
+        Some(Command::Replay { file, play: args }) => play(cli, args, Some(recording::load(file)?)),
+        Some(Command::Simulate { scripts }) => script::run(scripts),
+        Some(Command::ValidateVehicle { file }) => vehicle::validate(file),
+        Some(Command::Golden(args)) => headless::run(args),
+    }
 }
 
 #[allow(clippy::too_many_lines)]
-fn main() -> Result<(), String> {
-    let cli = Cli::parse();
-    simple_logger::SimpleLogger::new()
-        .with_level(cli.log_level)
-        .init()
-        .map_err(|err| err.to_string())?;
-
-    let sdl_context = sdl2::init()?;
-    let controller_system = sdl_context.game_controller()?;
-    let window = prepare_window(&sdl_context, !cli.windowed)?;
-    let (width, height) = window_size(&window)?;
-    let mut canvas = prepare_canvas(window)?;
+fn play(cli: &Cli, args: &PlayArgs, replay: Option<Recording>) -> Result<(), Error> {
+    let mut profile = settings::Profile::load(cli)?;
+    let mut settings = profile.stored.clone().with_args(args);
+    let assets = assets::Assets::new(args.assets.as_deref());
+    let requested = settings.theme != settings::Settings::default().theme;
+    settings.theme = assets.theme(&settings.theme, requested)?;
+
+    let sdl_context = sdl2::init().map_err(Error::video)?;
+    let controller_system = sdl_context.game_controller().map_err(Error::input)?;
+    let window = prepare_window(
+        &sdl_context,
+        !settings.windowed,
+        (settings.width, settings.height),
+    )?;
+    let mut size = window_size(&window)?;
+    let mut canvas = frame::canvas(window, settings.renderer, settings.vsync, || {
+        prepare_window(
+            &sdl_context,
+            !settings.windowed,
+            (settings.width, settings.height),
+        )
+    })?;
 
     let texture_creator = canvas.texture_creator();
-    let texture = texture_creator.load_texture(Path::new("assets/tile.png"))?;
-
-    let mut rpm: f64 = 0.0;
-    let mut kmh: f64 = 0.0;
-    let mut previous_gear = Gear::Neutral;
-    let mut clutch_cooldown = ClutchCooldown::default();
-    let mut hand = Hand::default();
-    let mut gear_stick = GearStick::default();
-    let mut input = Input::with_sensitivity(cli.mouse_sensitivity);
-
-    match check_for_controllers(&mut input, &controller_system) {
-        Ok(_) => log::info!("controller connected"),
+    #[cfg(feature = "ttf")]
+    let ttf_context = sdl2::ttf::init().map_err(Error::video)?;
+    let load_theme = |directory: &Path| -> Result<(Theme, Sprites), Error> {
+        let (theme, atlas) = assets::load_theme(directory)?;
+        let sprites = Sprites::load(&texture_creator, atlas)?;
+        #[cfg(feature = "ttf")]
+        let sprites = match &args.font {
+            Some(path) => sprites.with_true_type(atlas::TrueType {
+                path: path.clone(),
+                font: ttf_context
+                    .load_font(path, 40)
+                    .map_err(|e| Error::asset(path, format!("unable to load font: {e}")))?,
+                texture_creator: &texture_creator,
+            }),
+            None => sprites,
+        };
+        Ok((theme, sprites))
+    };
+    let (mut theme, mut sprites) = load_theme(&settings.theme)?;
+    let mut loaded_theme = settings.theme.clone();
+
+    let mut options = draw::Options {
+        cluster: settings.cluster,
+        units: settings.units,
+    };
+    let mut menu = menu::Menu::new(menu::themes(&settings.theme));
+    let benchmarks = timing::benchmarks(&args.intervals);
+    let mut players = vec![
+        Player::new(Input::with_sensitivity(settings.mouse_sensitivity))
+            .with_benchmarks(&benchmarks),
+    ];
+    let mut pool = HashMap::new();
+
+    match check_for_controllers(&mut pool, &controller_system) {
+        Ok(_) => log::info!("controller connected, press a button to join"),
         Err(err) => {
             log::debug!("error connecting controller: {err}");
         }
     };
 
-    'game_loop: loop {
-        canvas.set_draw_color(Color::RGB(1, 25, 54));
-        canvas.clear();
-        sdl_context.mouse().set_relative_mouse_mode(true);
+    let start = Instant::now();
+    let mut clock = frame::Clock::new(
+        settings
+            .max_fps
+            .or((!settings.vsync).then_some(frame::DEFAULT_MAX_FPS)),
+    );
+    let mut debug_overlay = false;
+    let mut screenshot = false;
+    let mut frame_capture = args
+        .capture_frames
+        .clone()
+        .map(capture::FrameCapture::new)
+        .transpose()?;
+    let mut recorder = args
+        .record
+        .as_deref()
+        .map(|path| recording::Recorder::create(path, &settings.assists))
+        .transpose()?;
+    let mut replay = replay.map(|recording| {
+        settings.assists = recording.assists;
+        recording.entries.into_iter()
+    });
+    let mut tutorial = args.tutorial.then(Tutorial::default);
+    let mut race = args.drag.then(Race::default);
+    let data_directory = assets::data_home();
+    let times_path = data_directory
+        .as_ref()
+        .map(|directory| directory.join("times.toml"));
+    let ghost_path = data_directory
+        .as_ref()
+        .map(|directory| directory.join("ghost.toml"));
+    let mut ghost = match (&ghost_path, args.ghost) {
+        (Some(path), true) => Ghost::load(path)?,
+        _ => None,
+    };
+    let mut records = match &times_path {
+        Some(path) => Records::load(path)?,
+        None => Records::default(),
+    };
+    let stored_times = records.best(vehicle::BUILT_IN, &benchmarks);
+    let mut telemetry_panel = telemetry::Panel {
+        visible: settings.telemetry,
+        corner: settings.telemetry_corner,
+        seconds: settings.telemetry_seconds,
+    };
 
-        let hand_offset = hand.next_offset();
-        hand.set_origin(hand_offset);
+    let result = 'game_loop: loop {
+        canvas.set_draw_color(theme.colors.background);
+        canvas.clear();
+        sdl_context.mouse().set_relative_mouse_mode(!menu.open);
+
+        let gears: Vec<Gear> = players.iter().map(Player::gear).collect();
+        let count = i16::try_from(players.len()).map_err(Error::video)?;
+
+        for (index, (player, gear)) in (0..count).zip(players.iter().zip(&gears)) {
+            let layout = draw::pane(&mut canvas, size, index, count, settings.scale)?;
+            let run = race
+                .as_ref()
+                .and_then(|race| race.runs.get(index as usize))
+                .filter(|run| !run.finished());
+
+            if settings.scene == Scene::Road {
+                let shake = if settings.camera_shake {
+                    scene::camera_shake(player.rpm, player.jolt, start.elapsed().as_secs_f64())
+                } else {
+                    (0, 0)
+                };
+                scene::road(
+                    &mut canvas,
+                    &theme.road,
+                    &layout,
+                    player.odometer * 1000.0,
+                    shake,
+                )?;
+            }
+
+            draw::all(
+                &mut canvas,
+                &sprites,
+                &theme,
+                &options,
+                &layout,
+                &draw::Peripherals {
+                    rpm: player.rpm,
+                    kmh: player.kmh,
+                    gear,
+                    odometer: player.odometer,
+                    trip: player.trip,
+                    time: Local::now().time(),
+                    ghost: run
+                        .zip(ghost.as_ref())
+                        .and_then(|(run, ghost)| ghost.replay(run.trace())),
+                },
+                player.gear_stick.offset,
+                &draw::Hand {
+                    offset: player.hand.offset,
+                    grabbing: player.input.action_active(&Action::Grab),
+                },
+                &draw::Pedals {
+                    travel: player.pedals,
+                    trace: settings.pedal_traces.then_some(&player.pedal_trace),
+                },
+            )?;
+
+            if telemetry_panel.visible {
+                telemetry::panel(
+                    &mut canvas,
+                    &sprites,
+                    &theme,
+                    &layout,
+                    options.units,
+                    &telemetry_panel,
+                    &player.telemetry,
+                )?;
+            }
+
+            scoring::popup(&mut canvas, &sprites, &theme, &layout, &player.scoring)?;
+
+            if let (0, Some(tutorial)) = (index, &tutorial) {
+                tutorial::draw(
+                    &mut canvas,
+                    &sprites,
+                    &theme,
+                    &layout,
+                    options.units,
+                    player,
+                    tutorial,
+                )?;
+            }
+
+            if args.timer {
+                timing::draw(
+                    &mut canvas,
+                    &sprites,
+                    &theme,
+                    &layout,
+                    &player.timer,
+                    &stored_times,
+                )?;
+            }
+
+            if let Some(race) = &race {
+                drag::draw(
+                    &mut canvas,
+                    &sprites,
+                    &theme,
+                    &layout,
+                    options.units,
+                    race,
+                    index as usize,
+                )?;
+            }
+
+            if debug_overlay {
+                debug::overlay(&mut canvas, &sprites, &theme, &layout, player, gear, &clock)?;
+            }
+        }
 
-        let gear_stick_offset = gear_stick.next_offset();
-        gear_stick.set_origin(gear_stick_offset);
+        if menu.open {
+            let layout = draw::pane(&mut canvas, size, 0, 1, settings.scale)?;
+            menu.draw(&mut canvas, &sprites, &theme, &layout, &settings)?;
+        }
 
-        let gear = gear_stick.gear(input.action_active(&Action::Clutch));
+        draw::reset_pane(&mut canvas)?;
 
-        draw::all(
-            &mut canvas,
-            &texture,
-            (width, height),
-            &draw::Peripherals {
-                rpm,
-                kmh,
-                gear: &gear,
-            },
-            gear_stick_offset,
-            &draw::Hand {
-                offset: hand_offset,
-                grabbing: input.action_active(&Action::Grab),
-            },
-            &draw::Pedals {
-                speeder_down: input.action_active(&Action::Accelerate),
-                clutch_down: input.action_active(&Action::Clutch),
-                brake_down: input.action_active(&Action::Brake),
-            },
-        )?;
+        if screenshot {
+            screenshot = false;
+            match capture::screenshot(&canvas, &args.screenshots) {
+                Ok(path) => log::info!("saved screenshot {}", path.display()),
+                Err(err) => log::error!("{err}"),
+            }
+        }
+        if let Some(frame_capture) = &mut frame_capture {
+            frame_capture.capture(&canvas)?;
+        }
 
         canvas.present();
 
-        poll_events(&sdl_context, &mut input, &controller_system)?;
+        poll_events(
+            &sdl_context,
+            &mut players,
+            &mut pool,
+            &controller_system,
+            &mut size,
+            &benchmarks,
+            &settings.keys,
+        )?;
 
-        if input.action_active(&Action::Quit) {
+        if players
+            .iter()
+            .any(|player| player.input.action_active(&Action::Quit))
+        {
             break 'game_loop Ok(());
         }
 
-        hand.target = Hand::target(&input);
-        if gear_stick.held {
-            let target = if input.action_active(&Action::Clutch) {
-                clamp_clutch_down(hand.target, hand.offset)
-            } else {
-                clamp_clutch_up(hand.target, hand.offset, &gear)
+        clock.wait();
+        for _ in 0..clock.ticks() {
+            let pressed = |action: &Action| {
+                players
+                    .iter()
+                    .any(|player| player.input.action_pressed(action))
             };
-            hand.target = target;
-            gear_stick.target = target;
-        } else {
-            gear_stick.target = gear_stick.resting_target();
-        };
 
-        if input.action_active(&Action::Brake) {
-            let new = brake(kmh, rpm, &gear, input.brake_alpha);
-            kmh = new.kmh;
-            rpm = new.rpm;
-        }
+            if menu.open {
+                let navigation = menu::Navigation {
+                    up: pressed(&Action::MenuUp) || pressed(&Action::Accelerate),
+                    down: pressed(&Action::MenuDown) || pressed(&Action::Brake),
+                    left: pressed(&Action::MenuLeft),
+                    right: pressed(&Action::MenuRight),
+                    confirm: pressed(&Action::Confirm) || pressed(&Action::Grab),
+                    back: pressed(&Action::Menu),
+                    key: players.iter().find_map(|player| player.input.pressed_key),
+                };
+
+                let before = settings.clone();
+                match menu.navigate(&navigation, &mut settings) {
+                    Some(menu::Command::Quit) => break 'game_loop Ok(()),
+                    Some(menu::Command::Restart) => {
+                        players = players.into_iter().map(Player::restart).collect();
+                        if let Some(recorder) = &mut recorder {
+                            recorder.restart()?;
+                        }
+                        if let Some(tutorial) = &mut tutorial {
+                            *tutorial = Tutorial::default();
+                        }
+                        if let Some(race) = &mut race {
+                            *race = Race::default();
+                        }
+                    }
+                    Some(menu::Command::Apply) => {
+                        for player in &mut players {
+                            player.input.set_sensitivity(settings.mouse_sensitivity);
+                        }
+                        options.units = settings.units;
+                        if settings.assists != before.assists {
+                            if let Some(recorder) = &mut recorder {
+                                recorder.assists(&settings.assists)?;
+                            }
+                        }
+
+                        if settings.theme != loaded_theme {
+                            match load_theme(&settings.theme) {
+                                Ok((new_theme, new_sprites)) => {
+                                    theme = new_theme;
+                                    sprites = new_sprites;
+                                    loaded_theme = settings.theme.clone();
+                                }
+                                Err(err) => {
+                                    log::error!("{err}");
+                                    settings.theme = loaded_theme.clone();
+                                }
+                            }
+                        }
+
+                        if let Err(err) = profile.save(&before, &settings, &assets) {
+                            log::error!("{err}");
+                        }
+                    }
+                    Some(menu::Command::Resume) | None => {}
+                }
 
-        if gear == Gear::Neutral {
-            clutch_cooldown.active = false;
-            clutch_cooldown.timer = 0.0;
-            let new = update_rpm_in_neutral(kmh, rpm, &mut input);
-            kmh = new.kmh;
-            rpm = new.rpm;
-        } else if previous_gear == Gear::Neutral && !clutch_cooldown.active {
-            clutch_cooldown.active = true;
-            clutch_cooldown.start_rpm = rpm;
-        } else if clutch_cooldown.timer < 1.0 && clutch_cooldown.active {
-            clutch_cooldown.timer += 8.0 / 60.0;
-            let new = switch_into_gear(kmh, &clutch_cooldown, &mut input, &gear);
-            kmh = new.kmh;
-            rpm = new.rpm;
-        } else if clutch_cooldown.active && clutch_cooldown.timer >= 1.0 {
-            clutch_cooldown.active = false;
-            clutch_cooldown.timer = 0.0;
-        } else {
-            rpm = flywheel_rpm(rpm, &mut input, gear == Gear::Neutral);
-            kmh = expected_kmh(rpm, gear.gear_ratio());
+                for player in &mut players {
+                    player.input.tick();
+                }
+                continue;
+            }
+
+            if pressed(&Action::Menu) {
+                menu.show();
+            }
+            if pressed(&Action::DebugOverlay) {
+                debug_overlay = !debug_overlay;
+            }
+            if pressed(&Action::Telemetry) {
+                telemetry_panel.visible = !telemetry_panel.visible;
+            }
+            if pressed(&Action::MoveTelemetry) {
+                telemetry_panel.corner = telemetry_panel.corner.next();
+            }
+            if pressed(&Action::Screenshot) {
+                screenshot = true;
+            }
+
+            if let Some(entries) = &mut replay {
+                match entries.next() {
+                    Some(recording::Entry::Tick(frame)) => {
+                        if let Some(player) = players.first_mut() {
+                            frame.apply(&mut player.input);
+                        }
+                    }
+                    Some(recording::Entry::Restart) => {
+                        players = players.into_iter().map(Player::restart).collect();
+                        if let Some(tutorial) = &mut tutorial {
+                            *tutorial = Tutorial::default();
+                        }
+                        if let Some(race) = &mut race {
+                            *race = Race::default();
+                        }
+                        continue;
+                    }
+                    Some(recording::Entry::Assists(assists)) => {
+                        settings.assists = assists;
+                        continue;
+                    }
+                    None => {
+                        log::info!("replay finished");
+                        break 'game_loop Ok(());
+                    }
+                }
+            }
+            if let (Some(recorder), Some(player)) = (&mut recorder, players.first()) {
+                recorder.tick(&recording::Frame::capture(&player.input))?;
+            }
+
+            for player in &mut players {
+                player.move_towards_targets();
+                let gear = player.gear();
+                player.update(gear, &settings.assists);
+            }
+            if let (Some(tutorial), Some(player)) = (&mut tutorial, players.first()) {
+                tutorial.update(player);
+            }
+            if let Some(race) = &mut race {
+                let finished = race.update(&players);
+                let fastest = finished
+                    .iter()
+                    .filter_map(|index| Some((race.runs[*index].clean_time()?, *index)))
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let (true, Some((time, index))) = (args.ghost, fastest) {
+                    if ghost.as_ref().is_none_or(|ghost| time < ghost.time) {
+                        log::info!("player {} set a new ghost: {time:.3}s", index + 1);
+                        ghost = Ghost::new(time, race.runs[index].trace());
+                        if let (Some(ghost), Some(path)) = (&ghost, &ghost_path) {
+                            if let Err(err) = ghost.save(path) {
+                                log::error!("{err}");
+                            }
+                        }
+                    }
+                }
+            }
         }
+    };
 
-        if input.action_changed(&Action::Grab) {
-            let x_square = (hand_offset.0 - gear_stick_offset.0).powi(2);
-            let y_square = (hand_offset.1 - gear_stick_offset.1).powi(2);
-            let distance = (x_square + y_square).sqrt();
-
-            gear_stick.held = input.action_active(&Action::Grab) && distance < 0.5;
+    for (index, player) in players.iter().enumerate() {
+        println!("player {}: {}", index + 1, player.scoring.summary());
+        println!("player {}: {}", index + 1, player.timer.summary());
+        if let Some(run) = race.as_ref().and_then(|race| race.runs.get(index)) {
+            println!("player {}: {}", index + 1, run.summary());
         }
+    }
 
-        input.action_tick(Action::Grab);
-        input.action_tick(Action::Clutch);
-
-        previous_gear = gear;
-
-        std::thread::sleep(Duration::new(0, 1_000_000_000u32 / 60));
+    let mut improved = false;
+    for player in &players {
+        improved |= records.update(vehicle::BUILT_IN, &player.timer);
+    }
+    if let (true, Some(path)) = (improved, &times_path) {
+        if let Err(err) = records.save(path) {
+            log::error!("{err}");
+        }
     }
+    result
 }
@@ -0,0 +1,397 @@
+use std::path::{Path, PathBuf};
+
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::keyboard::Keycode;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::error::Error;
+use crate::input::Action;
+use crate::layout::{Anchor, Layout};
+use crate::settings::{Settings, MAX_SENSITIVITY, MAX_VOLUME, MIN_SENSITIVITY};
+use crate::text::{draw_text, Align, TextStyle};
+use crate::theme::Theme;
+use crate::units::Units;
+
+const WIDTH: i16 = 800;
+const LINE_HEIGHT: i16 = 48;
+const SENSITIVITY_STEP: f64 = 16.0;
+const VOLUME_STEP: u8 = 10;
+
+const CONTROLS: [&str; 4] = [
+    "mouse / right stick  hand",
+    "left click / rb  grab",
+    "rt lt lb  pedals",
+    "esc / start  menu",
+];
+
+pub enum Command {
+    Resume,
+    Restart,
+    Apply,
+    Quit,
+}
+
+pub struct Navigation {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+    pub confirm: bool,
+    pub back: bool,
+    pub key: Option<Keycode>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Page {
+    Pause,
+    Settings,
+    Controls,
+}
+
+#[derive(Clone, Copy)]
+enum Item {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+    Sensitivity,
+    Units,
+    Theme,
+    RevMatch,
+    Volume,
+    Controls,
+    Bind(Action),
+    Back,
+}
+
+impl Page {
+    fn items(self) -> &'static [Item] {
+        match self {
+            Page::Pause => &[Item::Resume, Item::Restart, Item::Settings, Item::Quit],
+            Page::Settings => &[
+                Item::Sensitivity,
+                Item::Units,
+                Item::Theme,
+                Item::RevMatch,
+                Item::Volume,
+                Item::Controls,
+                Item::Back,
+            ],
+            Page::Controls => &[
+                Item::Bind(Action::Grab),
+                Item::Bind(Action::Accelerate),
+                Item::Bind(Action::Brake),
+                Item::Bind(Action::Clutch),
+                Item::Bind(Action::ResetTrip),
+                Item::Bind(Action::DebugOverlay),
+                Item::Bind(Action::Telemetry),
+                Item::Bind(Action::MoveTelemetry),
+                Item::Bind(Action::Screenshot),
+                Item::Back,
+            ],
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Page::Pause => "paused",
+            Page::Settings => "settings",
+            Page::Controls => "controls",
+        }
+    }
+}
+
+fn theme_name(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    )
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+fn label(item: Item, settings: &Settings, binding: Option<Action>) -> String {
+    match item {
+        Item::Resume => "resume".to_string(),
+        Item::Restart => "restart".to_string(),
+        Item::Settings => "settings".to_string(),
+        Item::Quit => "quit".to_string(),
+        Item::Sensitivity => format!("sensitivity  < {:.0} >", settings.mouse_sensitivity),
+        Item::Units => format!(
+            "units  < {} >",
+            match settings.units {
+                Units::Metric => "metric",
+                Units::Imperial => "imperial",
+            }
+        ),
+        Item::Theme => format!("theme  < {} >", theme_name(&settings.theme)),
+        Item::RevMatch => format!("rev matching  < {} >", on_off(settings.assists.rev_match)),
+        Item::Volume => format!("volume  < {} >", settings.volume),
+        Item::Controls => "controls".to_string(),
+        Item::Bind(action) if binding == Some(action) => {
+            format!("{}  < press a key >", action.name())
+        }
+        Item::Bind(action) => format!(
+            "{}  < {} >",
+            action.name(),
+            settings
+                .keys
+                .key(action)
+                .map_or_else(|| "none".to_string(), Keycode::name)
+        ),
+        Item::Back => "back".to_string(),
+    }
+}
+
+pub fn themes(current: &Path) -> Vec<PathBuf> {
+    let directory = current.parent().unwrap_or_else(|| Path::new("."));
+    let mut themes: Vec<PathBuf> = std::fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| path.join("theme.toml").is_file())
+                .collect()
+        })
+        .unwrap_or_default();
+    themes.sort();
+
+    if !themes.iter().any(|theme| theme == current) {
+        themes.insert(0, current.to_path_buf());
+    }
+    themes
+}
+
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, step: isize) -> T {
+    let index = values
+        .iter()
+        .position(|value| value == current)
+        .unwrap_or(0);
+    let len = values.len().max(1) as isize;
+    let next = (index as isize + step).rem_euclid(len) as usize;
+    values.get(next).cloned().unwrap_or_else(|| current.clone())
+}
+
+pub struct Menu {
+    pub open: bool,
+    page: Page,
+    selected: usize,
+    themes: Vec<PathBuf>,
+    binding: Option<Action>,
+}
+
+impl Menu {
+    pub fn new(themes: Vec<PathBuf>) -> Self {
+        Self {
+            open: false,
+            page: Page::Pause,
+            selected: 0,
+            themes,
+            binding: None,
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.open = true;
+        self.page = Page::Pause;
+        self.selected = 0;
+    }
+
+    fn go_to(&mut self, page: Page) {
+        self.page = page;
+        self.selected = 0;
+    }
+
+    fn adjust(&self, item: Item, settings: &mut Settings, step: isize) -> bool {
+        match item {
+            Item::Sensitivity => {
+                settings.mouse_sensitivity = (settings.mouse_sensitivity
+                    + SENSITIVITY_STEP * step as f64)
+                    .clamp(MIN_SENSITIVITY, MAX_SENSITIVITY);
+            }
+            Item::Units => {
+                settings.units = cycle(&[Units::Metric, Units::Imperial], &settings.units, step);
+            }
+            Item::Theme => settings.theme = cycle(&self.themes, &settings.theme, step),
+            Item::RevMatch => settings.assists.rev_match = !settings.assists.rev_match,
+            Item::Volume => {
+                settings.volume = if step < 0 {
+                    settings.volume.saturating_sub(VOLUME_STEP)
+                } else {
+                    settings.volume.saturating_add(VOLUME_STEP).min(MAX_VOLUME)
+                };
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn navigate(
+        &mut self,
+        navigation: &Navigation,
+        settings: &mut Settings,
+    ) -> Option<Command> {
+        let items = self.page.items();
+
+        if let Some(action) = self.binding {
+            if navigation.back {
+                self.binding = None;
+                return None;
+            }
+            let key = navigation.key?;
+            if !settings.keys.bind(action, key) {
+                return None;
+            }
+            self.binding = None;
+            return Some(Command::Apply);
+        }
+
+        if navigation.back {
+            return match self.page {
+                Page::Pause => {
+                    self.open = false;
+                    Some(Command::Resume)
+                }
+                Page::Settings => {
+                    self.go_to(Page::Pause);
+                    None
+                }
+                Page::Controls => {
+                    self.go_to(Page::Settings);
+                    None
+                }
+            };
+        }
+
+        if navigation.up {
+            self.selected = (self.selected + items.len() - 1) % items.len();
+        }
+        if navigation.down {
+            self.selected = (self.selected + 1) % items.len();
+        }
+
+        let item = items[self.selected.min(items.len() - 1)];
+        let step = match (navigation.left, navigation.right) {
+            (true, false) => -1,
+            (false, true) => 1,
+            _ => 0,
+        };
+        if step != 0 && self.adjust(item, settings, step) {
+            return Some(Command::Apply);
+        }
+
+        if !navigation.confirm {
+            return None;
+        }
+
+        match item {
+            Item::Resume => {
+                self.open = false;
+                Some(Command::Resume)
+            }
+            Item::Restart => {
+                self.open = false;
+                Some(Command::Restart)
+            }
+            Item::Quit => Some(Command::Quit),
+            Item::Settings => {
+                self.go_to(Page::Settings);
+                None
+            }
+            Item::Controls => {
+                self.go_to(Page::Controls);
+                None
+            }
+            Item::Bind(action) => {
+                self.binding = Some(action);
+                None
+            }
+            Item::Back => {
+                self.go_to(if self.page == Page::Controls {
+                    Page::Settings
+                } else {
+                    Page::Pause
+                });
+                None
+            }
+            item => self.adjust(item, settings, 1).then_some(Command::Apply),
+        }
+    }
+
+    pub fn draw<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        sprites: &Sprites,
+        theme: &Theme,
+        layout: &Layout,
+        settings: &Settings,
+    ) -> Result<(), Error> {
+        let text = Color::from(theme.colors.text);
+        let items = self.page.items();
+        let extra: &[&str] = if self.page == Page::Controls {
+            &CONTROLS
+        } else {
+            &[]
+        };
+        let lines = i16::try_from(items.len() + extra.len()).map_err(Error::video)?;
+        let height = (lines + 2) * LINE_HEIGHT;
+
+        let (center_x, center_y) = layout.anchor(&Anchor::Center, (0, 0));
+        let (left, top) = (center_x - WIDTH / 2, center_y - height / 2);
+        canvas
+            .box_(
+                left,
+                top,
+                left + WIDTH,
+                top + height,
+                Color::from(theme.hud.menu),
+            )
+            .map_err(Error::video)?;
+
+        draw_text(
+            canvas,
+            sprites,
+            self.page.title(),
+            (center_x, top + 16),
+            &TextStyle::new(6, text).aligned(Align::Center),
+        )?;
+
+        let mut y = top + 2 * LINE_HEIGHT;
+        for line in extra {
+            draw_text(
+                canvas,
+                sprites,
+                line,
+                (center_x, y),
+                &TextStyle::new(3, text).aligned(Align::Center),
+            )?;
+            y += LINE_HEIGHT;
+        }
+
+        for (index, item) in items.iter().enumerate() {
+            let color = if index == self.selected {
+                theme.hud.selected.into()
+            } else {
+                text
+            };
+            draw_text(
+                canvas,
+                sprites,
+                &label(*item, settings, self.binding),
+                (center_x, y),
+                &TextStyle::new(4, color).aligned(Align::Center),
+            )?;
+            y += LINE_HEIGHT;
+        }
+
+        Ok(())
+    }
+}
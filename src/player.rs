@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+
+use crate::drivetrain::{
+    brake, flywheel_rpm, slip_clutch, switch_into_gear, update_rpm_in_neutral, ClutchCooldown,
+};
+use crate::gear_stick::{expected_kmh, expected_rpm, Gear, GearStick};
+use crate::hand::{clamp_clutch_down, clamp_clutch_up, Hand};
+use crate::input::{Action, Input};
+use crate::scoring::{Scoring, GRIND_DISTANCE};
+use crate::settings::Assists;
+use crate::simulation::TICK_SECONDS;
+use crate::telemetry::Telemetry;
+use crate::timing::{Benchmark, Timer};
+
+pub const GRAB_RADIUS: f64 = 0.5;
+const CLUTCH_TRAVEL_PER_TICK: f64 = 6.0 * TICK_SECONDS;
+const PEDAL_TRACE_LENGTH: usize = 120;
+
+#[derive(Clone, Copy, Default)]
+pub struct PedalTravel {
+    pub clutch: f64,
+    pub brake: f64,
+    pub throttle: f64,
+}
+
+pub struct Player {
+    pub input: Input,
+    pub hand: Hand,
+    pub gear_stick: GearStick,
+    pub rpm: f64,
+    pub kmh: f64,
+    pub previous_gear: Gear,
+    pub clutch_cooldown: ClutchCooldown,
+    pub odometer: f64,
+    pub trip: f64,
+    pub jolt: f64,
+    pub pedals: PedalTravel,
+    pub pedal_trace: VecDeque<PedalTravel>,
+    pub telemetry: Telemetry,
+    pub scoring: Scoring,
+    pub timer: Timer,
+}
+
+impl Player {
+    pub fn new(input: Input) -> Self {
+        Self {
+            input,
+            hand: Hand::default(),
+            gear_stick: GearStick::default(),
+            rpm: 0.0,
+            kmh: 0.0,
+            previous_gear: Gear::Neutral,
+            clutch_cooldown: ClutchCooldown::default(),
+            odometer: 0.0,
+            trip: 0.0,
+            jolt: 0.0,
+            pedals: PedalTravel::default(),
+            pedal_trace: VecDeque::with_capacity(PEDAL_TRACE_LENGTH),
+            telemetry: Telemetry::default(),
+            scoring: Scoring::default(),
+            timer: Timer::default(),
+        }
+    }
+
+    pub fn with_benchmarks(self, benchmarks: &[Benchmark]) -> Self {
+        Self {
+            timer: Timer::new(benchmarks.to_vec()),
+            ..self
+        }
+    }
+
+    pub fn move_towards_targets(&mut self) {
+        let hand_offset = self.hand.next_offset();
+        self.hand.set_origin(hand_offset);
+
+        let gear_stick_offset = self.gear_stick.next_offset();
+        self.gear_stick.set_origin(gear_stick_offset);
+    }
+
+    pub fn gear(&self) -> Gear {
+        self.gear_stick
+            .gear(self.input.action_active(&Action::Clutch))
+    }
+
+    fn update_pedals(&mut self) {
+        let pressed = |active: bool, alpha: f64| if active { alpha } else { 0.0 };
+        let clutch_target = pressed(
+            self.input.action_active(&Action::Clutch),
+            self.input.clutch_alpha,
+        );
+        let clutch = self.pedals.clutch;
+
+        self.pedals = PedalTravel {
+            clutch: clutch
+                + (clutch_target - clutch).clamp(-CLUTCH_TRAVEL_PER_TICK, CLUTCH_TRAVEL_PER_TICK),
+            brake: pressed(
+                self.input.action_active(&Action::Brake),
+                self.input.brake_alpha,
+            ),
+            throttle: pressed(
+                self.input.action_active(&Action::Accelerate),
+                self.input.speeder_alpha,
+            ),
+        };
+
+        if self.pedal_trace.len() == PEDAL_TRACE_LENGTH {
+            self.pedal_trace.pop_front();
+        }
+        self.pedal_trace.push_back(self.pedals);
+    }
+
+    pub fn restart(self) -> Self {
+        let Self {
+            input,
+            mut scoring,
+            mut timer,
+            ..
+        } = self;
+        scoring.restart();
+        timer.restart();
+        Self {
+            scoring,
+            timer,
+            ..Self::new(input)
+        }
+    }
+
+    pub fn update(&mut self, gear: Gear, assists: &Assists) {
+        let hand_offset = self.hand.offset;
+        let gear_stick_offset = self.gear_stick.offset;
+
+        let wanted = Hand::target(&self.input);
+        self.hand.target = wanted;
+        if self.gear_stick.held {
+            let target = if self.input.action_active(&Action::Clutch) {
+                clamp_clutch_down(self.hand.target, self.hand.offset)
+            } else {
+                clamp_clutch_up(self.hand.target, self.hand.offset, &gear)
+            };
+            self.hand.target = target;
+            self.gear_stick.target = target;
+        } else {
+            self.gear_stick.target = self.gear_stick.resting_target();
+        };
+
+        if self.input.action_active(&Action::Brake) {
+            let new = brake(self.kmh, self.rpm, &gear, self.input.brake_alpha);
+            self.kmh = new.kmh;
+            self.rpm = new.rpm;
+        }
+
+        let clutch_cooldown = &mut self.clutch_cooldown;
+        if gear == Gear::Neutral {
+            clutch_cooldown.active = false;
+            clutch_cooldown.timer = 0.0;
+            let new = update_rpm_in_neutral(self.kmh, self.rpm, &mut self.input);
+            self.kmh = new.kmh;
+            self.rpm = new.rpm;
+
+            let selected = self.gear_stick.gear(false);
+            if selected != Gear::Neutral {
+                let new = slip_clutch(self.kmh, self.rpm, &selected, 1.0 - self.pedals.clutch);
+                self.kmh = new.kmh;
+                self.rpm = new.rpm;
+            }
+        } else if self.previous_gear == Gear::Neutral && !clutch_cooldown.active {
+            let target = expected_rpm(self.kmh, gear.gear_ratio());
+            if assists.rev_match {
+                self.rpm = target;
+            }
+            clutch_cooldown.active = true;
+            clutch_cooldown.start_rpm = self.rpm;
+            self.jolt = ((self.rpm - target).abs() / 3000.0).min(1.0);
+        } else if clutch_cooldown.timer < 1.0 && clutch_cooldown.active {
+            clutch_cooldown.timer += 8.0 / 60.0;
+            let new = switch_into_gear(self.kmh, clutch_cooldown, &mut self.input, &gear);
+            self.kmh = new.kmh;
+            self.rpm = new.rpm;
+        } else if clutch_cooldown.active && clutch_cooldown.timer >= 1.0 {
+            clutch_cooldown.active = false;
+            clutch_cooldown.timer = 0.0;
+        } else {
+            self.rpm = flywheel_rpm(self.rpm, &mut self.input, gear == Gear::Neutral);
+            self.kmh = expected_kmh(self.rpm, gear.gear_ratio());
+        }
+
+        if self.input.action_changed(&Action::Grab) {
+            let x_square = (hand_offset.0 - gear_stick_offset.0).powi(2);
+            let y_square = (hand_offset.1 - gear_stick_offset.1).powi(2);
+            let distance = (x_square + y_square).sqrt();
+
+            self.gear_stick.held =
+                self.input.action_active(&Action::Grab) && distance < GRAB_RADIUS;
+        }
+
+        self.jolt *= 0.85;
+        self.update_pedals();
+        self.telemetry.record(
+            self.rpm,
+            self.kmh,
+            self.pedals,
+            &gear,
+            gear != self.previous_gear && gear != Gear::Neutral,
+        );
+
+        let clutch = self.input.action_active(&Action::Clutch);
+        let (x, y) = self.hand.target;
+        let forcing =
+            self.gear_stick.held && !clutch && (wanted.0 - x).hypot(wanted.1 - y) > GRIND_DISTANCE;
+        self.scoring.record(
+            gear,
+            self.gear_stick.gear(false),
+            self.rpm,
+            self.kmh,
+            forcing,
+        );
+        self.timer.record(self.kmh, gear);
+
+        let distance = self.kmh.abs() / 3600.0 / 60.0;
+        self.odometer += distance;
+        self.trip += distance;
+
+        if self.input.action_pressed(&Action::ResetTrip) {
+            self.trip = 0.0;
+        }
+
+        self.input.tick();
+
+        self.previous_gear = gear;
+    }
+}
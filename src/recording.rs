@@ -0,0 +1,191 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::Error;
+use crate::input::{Action, Input};
+use crate::settings::Assists;
+
+const HEADER: &str = "car-demo recording 1";
+const RESTART: &str = "restart";
+
+const RECORDED: [Action; 5] = [
+    Action::Grab,
+    Action::Accelerate,
+    Action::Brake,
+    Action::Clutch,
+    Action::ResetTrip,
+];
+
+pub struct Frame {
+    hand: (f64, f64),
+    speeder_alpha: f64,
+    brake_alpha: f64,
+    actions: [bool; RECORDED.len()],
+}
+
+pub enum Entry {
+    Tick(Frame),
+    Restart,
+    Assists(Assists),
+}
+
+pub struct Recording {
+    pub assists: Assists,
+    pub entries: Vec<Entry>,
+}
+
+impl Frame {
+    pub fn capture(input: &Input) -> Self {
+        Self {
+            hand: input.hand,
+            speeder_alpha: input.speeder_alpha,
+            brake_alpha: input.brake_alpha,
+            actions: RECORDED.map(|action| input.action_active(&action)),
+        }
+    }
+
+    pub fn apply(&self, input: &mut Input) {
+        input.hand = self.hand;
+        input.speeder_alpha = self.speeder_alpha;
+        input.brake_alpha = self.brake_alpha;
+
+        for (action, active) in RECORDED.into_iter().zip(self.actions) {
+            if active {
+                input.key_down(action);
+            } else if input.action_active(&action) {
+                input.key_up(action);
+            }
+        }
+    }
+
+    fn parse(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [x, y, speeder_alpha, brake_alpha, actions] = fields[..] else {
+            return Err(format!("expected 5 fields, got {}", fields.len()));
+        };
+        let number = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|e| format!("invalid number `{value}`: {e}"))
+        };
+
+        let mut flags = [false; RECORDED.len()];
+        if actions.len() != flags.len() {
+            return Err(format!("expected {} action flags", flags.len()));
+        }
+        for (flag, character) in flags.iter_mut().zip(actions.chars()) {
+            *flag = match character {
+                '0' => false,
+                '1' => true,
+                other => return Err(format!("invalid action flag `{other}`")),
+            };
+        }
+
+        Ok(Self {
+            hand: (number(x)?, number(y)?),
+            speeder_alpha: number(speeder_alpha)?,
+            brake_alpha: number(brake_alpha)?,
+            actions: flags,
+        })
+    }
+}
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path, assists: &Assists) -> Result<Self, Error> {
+        let file = File::create(path).map_err(|e| {
+            Error::input(format!(
+                "unable to create recording {}: {e}",
+                path.display()
+            ))
+        })?;
+        let mut recorder = Self {
+            writer: BufWriter::new(file),
+        };
+        recorder.line(HEADER)?;
+        recorder.assists(assists)?;
+        Ok(recorder)
+    }
+
+    fn line(&mut self, line: &str) -> Result<(), Error> {
+        writeln!(self.writer, "{line}")
+            .map_err(|e| Error::input(format!("unable to write recording: {e}")))
+    }
+
+    pub fn tick(&mut self, frame: &Frame) -> Result<(), Error> {
+        let actions: String = frame
+            .actions
+            .iter()
+            .map(|active| if *active { '1' } else { '0' })
+            .collect();
+        self.line(&format!(
+            "{} {} {} {} {actions}",
+            frame.hand.0, frame.hand.1, frame.speeder_alpha, frame.brake_alpha
+        ))
+    }
+
+    pub fn restart(&mut self) -> Result<(), Error> {
+        self.line(RESTART)
+    }
+
+    pub fn assists(&mut self, assists: &Assists) -> Result<(), Error> {
+        self.line(&format!("rev_match {}", u8::from(assists.rev_match)))
+    }
+}
+
+fn parse_assists(line: &str) -> Option<Assists> {
+    match line {
+        "rev_match 0" => Some(Assists { rev_match: false }),
+        "rev_match 1" => Some(Assists { rev_match: true }),
+        _ => None,
+    }
+}
+
+pub fn load(path: &Path) -> Result<Recording, Error> {
+    let file = File::open(path)
+        .map_err(|e| Error::input(format!("unable to open recording {}: {e}", path.display())))?;
+    let mut lines = BufReader::new(file).lines();
+    let mut next_line = || {
+        lines
+            .next()
+            .transpose()
+            .map_err(|e| Error::input(format!("unable to read recording {}: {e}", path.display())))
+    };
+
+    if next_line()?.as_deref() != Some(HEADER) {
+        return Err(Error::input(format!(
+            "{} is not a car-demo recording",
+            path.display()
+        )));
+    }
+
+    let Some(assists) = next_line()?.as_deref().and_then(parse_assists) else {
+        return Err(Error::input(format!(
+            "{}: missing assists line",
+            path.display()
+        )));
+    };
+
+    let mut entries = Vec::new();
+    let mut number = 2;
+    while let Some(line) = next_line()? {
+        number += 1;
+        let entry = if line == RESTART {
+            Entry::Restart
+        } else if let Some(assists) = parse_assists(&line) {
+            Entry::Assists(assists)
+        } else {
+            Entry::Tick(
+                Frame::parse(&line)
+                    .map_err(|e| Error::input(format!("{} line {number}: {e}", path.display())))?,
+            )
+        };
+        entries.push(entry);
+    }
+
+    Ok(Recording { assists, entries })
+}
@@ -0,0 +1,159 @@
+use clap::ValueEnum;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::drivetrain::MAX_RPM;
+use crate::error::Error;
+use crate::layout::Layout;
+use crate::theme::Road;
+
+const PIXELS_PER_METER: f64 = 32.0;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Scene {
+    Road,
+    Plain,
+}
+
+fn noise(index: i64) -> f64 {
+    let mut x = index.wrapping_mul(0x5851_F42D_4C95_7F2D) as u64;
+    x ^= x >> 33;
+    x = x.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+    x ^= x >> 29;
+    (x % 1000) as f64 / 1000.0
+}
+
+fn scroll(distance: f64, parallax: f64, period: f64) -> (i64, i16) {
+    let position = distance * PIXELS_PER_METER * parallax;
+    let index = (position / period).floor();
+    let offset = position - index * period;
+    (index as i64, offset as i16)
+}
+
+fn clamped(value: i32) -> i16 {
+    value.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16
+}
+
+fn layer<F>(
+    width: i16,
+    distance: f64,
+    parallax: f64,
+    period: i16,
+    shake: i16,
+    mut draw: F,
+) -> Result<(), Error>
+where
+    F: FnMut(i32, f64) -> Result<(), String>,
+{
+    let (index, offset) = scroll(distance, parallax, f64::from(period));
+    let (width, period) = (i32::from(width), i32::from(period));
+
+    let mut x = i32::from(shake) - i32::from(offset) - period;
+    let mut item = index - 1;
+    while x < width + period + i32::from(shake) {
+        draw(x, noise(item)).map_err(Error::video)?;
+        x += period;
+        item += 1;
+    }
+
+    Ok(())
+}
+
+pub fn camera_shake(rpm: f64, jolt: f64, time: f64) -> (i16, i16) {
+    let amplitude = rpm / MAX_RPM * 2.0 + jolt * 16.0;
+
+    (
+        ((time * 53.0).sin() * amplitude) as i16,
+        ((time * 71.0).cos() * amplitude) as i16,
+    )
+}
+
+pub fn road<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    colors: &Road,
+    layout: &Layout,
+    distance: f64,
+    shake: (i16, i16),
+) -> Result<(), Error> {
+    let (width, height) = layout.size;
+    let (dx, dy) = shake;
+    let at = |fraction: f64| (f64::from(height) * fraction) as i16 + dy;
+    let horizon = at(0.25);
+    let road_top = at(0.4);
+    let road_bottom = at(0.55);
+
+    let mountains = Color::from(colors.mountains);
+    layer(width, distance, 0.02, 480, dx, |x, noise| {
+        let peak = horizon.saturating_sub(60 + (noise * 120.0) as i16);
+        canvas.filled_trigon(
+            clamped(x),
+            horizon,
+            clamped(x + 240),
+            peak,
+            clamped(x + 480),
+            horizon,
+            mountains,
+        )
+    })?;
+
+    canvas
+        .box_(
+            -64,
+            horizon,
+            width.saturating_add(64),
+            height.saturating_add(64),
+            Color::from(colors.grass),
+        )
+        .map_err(Error::video)?;
+
+    let hills = Color::from(colors.hills);
+    layer(width, distance, 0.2, 96, dx, |x, noise| {
+        let size = 16 + (noise * 24.0) as i16;
+        canvas.filled_trigon(
+            clamped(x),
+            road_top,
+            clamped(x + i32::from(size)),
+            road_top.saturating_sub(size * 2),
+            clamped(x + i32::from(size) * 2),
+            road_top,
+            hills,
+        )
+    })?;
+
+    canvas
+        .box_(
+            -64,
+            road_top,
+            width.saturating_add(64),
+            road_bottom,
+            Color::from(colors.asphalt),
+        )
+        .map_err(Error::video)?;
+
+    let markings = Color::from(colors.markings);
+    let lane = (road_top + road_bottom) / 2;
+    let right = width.saturating_add(64);
+    canvas
+        .box_(-64, road_top + 4, right, road_top + 8, markings)
+        .map_err(Error::video)?;
+    canvas
+        .box_(-64, road_bottom - 8, right, road_bottom - 4, markings)
+        .map_err(Error::video)?;
+    layer(width, distance, 1.0, 320, dx, |x, _| {
+        canvas.box_(clamped(x), lane - 4, clamped(x + 160), lane + 4, markings)
+    })?;
+
+    let posts = Color::from(colors.posts);
+    layer(width, distance, 1.3, 800, dx, |x, noise| {
+        let x = x + (noise * 400.0) as i32;
+        let (left, right) = (clamped(x), clamped(x + 12));
+        let top = road_bottom.saturating_add(8);
+        canvas.box_(left, top, right, road_bottom.saturating_add(72), posts)?;
+        canvas.box_(left, top, right, road_bottom.saturating_add(20), markings)
+    })?;
+
+    Ok(())
+}
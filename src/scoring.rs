@@ -0,0 +1,287 @@
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::drivetrain::REDLINE_RPM;
+use crate::error::Error;
+use crate::gear_stick::{expected_rpm, Gear};
+use crate::layout::{Anchor, Layout};
+use crate::simulation::TICK_SECONDS;
+use crate::text::{draw_text, text_width, Align, TextStyle};
+use crate::theme::{Hud, Theme};
+
+const NEUTRAL_ALLOWANCE: f64 = 0.4;
+const NEUTRAL_PENALTY: f64 = 40.0;
+const CLUTCH_ALLOWANCE: f64 = 0.25;
+const CLUTCH_PENALTY: f64 = 40.0;
+const MISMATCH_ALLOWANCE: f64 = 200.0;
+const MISMATCH_PENALTY: f64 = 10.0 / 500.0;
+const GRIND_PENALTY: f64 = 20.0;
+const OVER_REV_PENALTY: f64 = 25.0;
+pub const GRIND_DISTANCE: f64 = 0.5;
+
+const POPUP_TICKS: u64 = 120;
+const FADE_TICKS: u64 = 30;
+const POPUP_PADDING: i16 = 16;
+
+pub struct Shift {
+    pub from: Gear,
+    pub to: Gear,
+    pub neutral_time: f64,
+    pub clutch_delay: f64,
+    pub rpm_mismatch: f64,
+    pub grinds: u32,
+    pub over_revs: u32,
+    pub score: f64,
+}
+
+fn grade(score: f64) -> &'static str {
+    match score {
+        score if score >= 90.0 => "A",
+        score if score >= 75.0 => "B",
+        score if score >= 60.0 => "C",
+        score if score >= 40.0 => "D",
+        _ => "F",
+    }
+}
+
+fn grade_color(hud: &Hud, score: f64) -> Color {
+    match score {
+        score if score >= 75.0 => hud.good,
+        score if score >= 40.0 => hud.fair,
+        _ => hud.bad,
+    }
+    .into()
+}
+
+impl Shift {
+    fn penalties(&self) -> [(f64, String); 5] {
+        let over = |value: f64, allowance: f64| (value - allowance).max(0.0);
+        [
+            (
+                over(self.neutral_time, NEUTRAL_ALLOWANCE) * NEUTRAL_PENALTY,
+                format!("{:.1}s in neutral", self.neutral_time),
+            ),
+            (
+                over(self.clutch_delay, CLUTCH_ALLOWANCE) * CLUTCH_PENALTY,
+                format!("clutch out after {:.1}s", self.clutch_delay),
+            ),
+            (
+                over(self.rpm_mismatch, MISMATCH_ALLOWANCE) * MISMATCH_PENALTY,
+                format!("{:.0} rpm off", self.rpm_mismatch),
+            ),
+            (
+                f64::from(self.grinds) * GRIND_PENALTY,
+                format!("{} grind(s)", self.grinds),
+            ),
+            (
+                f64::from(self.over_revs) * OVER_REV_PENALTY,
+                format!("{} over-rev(s)", self.over_revs),
+            ),
+        ]
+    }
+
+    fn rate(mut self) -> Self {
+        let penalty: f64 = self.penalties().iter().map(|(points, _)| points).sum();
+        self.score = (100.0 - penalty).clamp(0.0, 100.0).round();
+        self
+    }
+
+    pub fn grade(&self) -> &'static str {
+        grade(self.score)
+    }
+
+    fn advice(&self) -> String {
+        self.penalties()
+            .into_iter()
+            .filter(|(points, _)| *points >= 1.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or_else(|| "clean".to_string(), |(_, advice)| advice)
+    }
+}
+
+struct Pending {
+    from: Gear,
+    left_at: u64,
+    in_gate_at: Option<u64>,
+    over_revving: bool,
+    over_revs: u32,
+}
+
+#[derive(Default)]
+pub struct Scoring {
+    pub shifts: Vec<Shift>,
+    ticks: u64,
+    gear: Option<Gear>,
+    pending: Option<Pending>,
+    forcing: bool,
+    grinds: u32,
+    popup_at: Option<u64>,
+}
+
+impl Scoring {
+    pub fn restart(&mut self) {
+        *self = Self {
+            shifts: std::mem::take(&mut self.shifts),
+            ..Self::default()
+        };
+    }
+
+    pub fn record(&mut self, gear: Gear, stick: Gear, rpm: f64, kmh: f64, forcing: bool) {
+        let previous = self.gear.replace(gear);
+        self.ticks += 1;
+
+        if forcing && !self.forcing {
+            self.grinds += 1;
+        }
+        self.forcing = forcing;
+
+        if gear == Gear::Neutral {
+            let ticks = self.ticks;
+            let pending = self.pending.get_or_insert_with(|| Pending {
+                from: previous.unwrap_or(Gear::Neutral),
+                left_at: ticks,
+                in_gate_at: None,
+                over_revving: false,
+                over_revs: 0,
+            });
+
+            if stick == Gear::Neutral {
+                pending.in_gate_at = None;
+            } else if pending.in_gate_at.is_none() {
+                pending.in_gate_at = Some(ticks);
+            }
+
+            let over_revving = rpm > REDLINE_RPM;
+            if over_revving && !pending.over_revving {
+                pending.over_revs += 1;
+            }
+            pending.over_revving = over_revving;
+            return;
+        }
+
+        if previous != Some(Gear::Neutral) {
+            return;
+        }
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+
+        let seconds = |ticks: u64| ticks as f64 * TICK_SECONDS;
+        let in_gate_at = pending.in_gate_at.unwrap_or(self.ticks);
+        let target = expected_rpm(kmh, gear.gear_ratio());
+        let launch = pending.from == Gear::Neutral;
+
+        let shift = Shift {
+            from: pending.from,
+            to: gear,
+            neutral_time: if launch {
+                0.0
+            } else {
+                seconds(in_gate_at - pending.left_at)
+            },
+            clutch_delay: seconds(self.ticks - in_gate_at),
+            rpm_mismatch: if launch { 0.0 } else { (rpm - target).abs() },
+            grinds: std::mem::take(&mut self.grinds),
+            over_revs: pending.over_revs + u32::from(target > REDLINE_RPM),
+            score: 0.0,
+        }
+        .rate();
+
+        log::debug!(
+            "shift {} > {}: {} {:.0} ({})",
+            shift.from.label(),
+            shift.to.label(),
+            shift.grade(),
+            shift.score,
+            shift.advice()
+        );
+        self.shifts.push(shift);
+        self.popup_at = Some(self.ticks);
+    }
+
+    pub fn summary(&self) -> String {
+        let count = self.shifts.len();
+        if count == 0 {
+            return "no shifts".to_string();
+        }
+
+        let scores = self.shifts.iter().map(|shift| shift.score);
+        let average = scores.clone().sum::<f64>() / count as f64;
+        let best = scores.clone().fold(0.0, f64::max);
+        let worst = scores.fold(100.0, f64::min);
+        let grinds: u32 = self.shifts.iter().map(|shift| shift.grinds).sum();
+        let over_revs: u32 = self.shifts.iter().map(|shift| shift.over_revs).sum();
+        let gear_changes: Vec<&Shift> = self
+            .shifts
+            .iter()
+            .filter(|shift| shift.from != Gear::Neutral)
+            .collect();
+        let mismatch = gear_changes
+            .iter()
+            .map(|shift| shift.rpm_mismatch)
+            .sum::<f64>()
+            / gear_changes.len().max(1) as f64;
+
+        format!(
+            "{count} shifts, average {average:.0} ({}), best {best:.0}, worst {worst:.0}, {grinds} grind(s), {over_revs} over-rev(s), {mismatch:.0} rpm average mismatch",
+            grade(average)
+        )
+    }
+}
+
+pub fn popup<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    scoring: &Scoring,
+) -> Result<(), Error> {
+    let (Some(popup_at), Some(shift)) = (scoring.popup_at, scoring.shifts.last()) else {
+        return Ok(());
+    };
+    let age = scoring.ticks - popup_at;
+    if age >= POPUP_TICKS {
+        return Ok(());
+    }
+
+    let remaining = (POPUP_TICKS - age).min(FADE_TICKS);
+    let fade = |color: Color| {
+        Color::RGBA(
+            color.r,
+            color.g,
+            color.b,
+            (u64::from(color.a) * remaining / FADE_TICKS) as u8,
+        )
+    };
+
+    let title = format!(
+        "{} > {}  {} {:.0}",
+        shift.from.label(),
+        shift.to.label(),
+        shift.grade(),
+        shift.score
+    );
+    let advice = shift.advice();
+    let title_style =
+        TextStyle::new(5, fade(grade_color(&theme.hud, shift.score))).aligned(Align::Center);
+    let advice_style = TextStyle::new(3, fade(theme.colors.text.into())).aligned(Align::Center);
+
+    let width =
+        text_width(sprites, &title, &title_style).max(text_width(sprites, &advice, &advice_style));
+    let half = i16::try_from(width / 2).unwrap_or(i16::MAX / 2) + POPUP_PADDING;
+    let (x, y) = layout.anchor(&Anchor::Center, (0, -240));
+
+    canvas
+        .box_(x - half, y, x + half, y + 112, fade(theme.hud.panel.into()))
+        .map_err(Error::video)?;
+    draw_text(
+        canvas,
+        sprites,
+        &title,
+        (x, y + POPUP_PADDING),
+        &title_style,
+    )?;
+    draw_text(canvas, sprites, &advice, (x, y + 72), &advice_style)
+}
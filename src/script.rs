@@ -0,0 +1,416 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::input::Action;
+use crate::player::Player;
+use crate::settings::Assists;
+use crate::simulation::Simulation;
+
+const DEFAULT_TRACE_INTERVAL: f64 = 0.5;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Step {
+    at: Option<f64>,
+    after: Option<f64>,
+    rpm: Option<f64>,
+    kmh: Option<f64>,
+    clutch: Option<bool>,
+    throttle: Option<f64>,
+    brake: Option<f64>,
+    over: Option<f64>,
+    gear: Option<Gear>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expectation {
+    that: String,
+    by: Option<f64>,
+    at: Option<f64>,
+}
+
+fn default_trace_interval() -> f64 {
+    DEFAULT_TRACE_INTERVAL
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Script {
+    duration: f64,
+    #[serde(default = "default_trace_interval")]
+    trace: f64,
+    #[serde(default)]
+    assists: Assists,
+    #[serde(default, rename = "step")]
+    steps: Vec<Step>,
+    #[serde(default, rename = "expect")]
+    expectations: Vec<Expectation>,
+}
+
+#[derive(Clone, Copy)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+enum Subject {
+    Rpm(f64),
+    Kmh(f64),
+    Gear(Gear),
+}
+
+struct Condition {
+    subject: Subject,
+    comparison: Comparison,
+}
+
+impl Comparison {
+    fn matches(self, ordering: Option<Ordering>) -> bool {
+        match self {
+            Comparison::Less => ordering == Some(Ordering::Less),
+            Comparison::LessOrEqual => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+            Comparison::Greater => ordering == Some(Ordering::Greater),
+            Comparison::GreaterOrEqual => {
+                matches!(ordering, Some(Ordering::Greater | Ordering::Equal))
+            }
+            Comparison::Equal => ordering == Some(Ordering::Equal),
+            Comparison::NotEqual => ordering != Some(Ordering::Equal),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let [subject, comparison, value] = tokens[..] else {
+            return Err(format!(
+                "`{text}` should look like `kmh > 100`, `rpm <= 7000` or `gear == second`"
+            ));
+        };
+
+        let comparison = match comparison {
+            "<" => Comparison::Less,
+            "<=" => Comparison::LessOrEqual,
+            ">" => Comparison::Greater,
+            ">=" => Comparison::GreaterOrEqual,
+            "==" => Comparison::Equal,
+            "!=" => Comparison::NotEqual,
+            other => return Err(format!("`{text}`: unknown comparison `{other}`")),
+        };
+        let number = || {
+            value
+                .parse::<f64>()
+                .map_err(|e| format!("`{text}`: invalid number `{value}`: {e}"))
+        };
+
+        let subject = match subject {
+            "rpm" => Subject::Rpm(number()?),
+            "kmh" => Subject::Kmh(number()?),
+            "gear" => {
+                if !matches!(comparison, Comparison::Equal | Comparison::NotEqual) {
+                    return Err(format!(
+                        "`{text}`: gears can only be compared with == or !="
+                    ));
+                }
+                Subject::Gear(
+                    Gear::parse(value)
+                        .ok_or_else(|| format!("`{text}`: unknown gear `{value}`"))?,
+                )
+            }
+            other => {
+                return Err(format!(
+                    "`{text}`: unknown value `{other}`, expected rpm, kmh or gear"
+                ))
+            }
+        };
+
+        Ok(Self {
+            subject,
+            comparison,
+        })
+    }
+}
+
+impl Condition {
+    fn holds(&self, player: &Player) -> bool {
+        let ordering = match self.subject {
+            Subject::Rpm(rpm) => player.rpm.partial_cmp(&rpm),
+            Subject::Kmh(kmh) => player.kmh.partial_cmp(&kmh),
+            Subject::Gear(gear) => Some(if player.gear() == gear {
+                Ordering::Equal
+            } else {
+                Ordering::Greater
+            }),
+        };
+        self.comparison.matches(ordering)
+    }
+}
+
+impl Step {
+    fn ready(&self, simulation: &Simulation, previous: f64) -> bool {
+        let time = simulation.time();
+        let player = &simulation.player;
+        self.at.is_none_or(|at| time >= at)
+            && self.after.is_none_or(|after| time >= previous + after)
+            && self.rpm.is_none_or(|rpm| player.rpm >= rpm)
+            && self.kmh.is_none_or(|kmh| player.kmh >= kmh)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct Ramp {
+    from: f64,
+    to: f64,
+    start: f64,
+    duration: f64,
+}
+
+impl Ramp {
+    fn level(&self, time: f64) -> f64 {
+        if self.duration <= 0.0 {
+            return self.to;
+        }
+        let progress = ((time - self.start) / self.duration).clamp(0.0, 1.0);
+        self.from + (self.to - self.from) * progress
+    }
+}
+
+struct Pedal {
+    action: Action,
+    ramp: Ramp,
+}
+
+impl Pedal {
+    fn new(action: Action) -> Self {
+        Self {
+            action,
+            ramp: Ramp::default(),
+        }
+    }
+
+    fn set(&mut self, level: f64, time: f64, duration: f64) {
+        self.ramp = Ramp {
+            from: self.ramp.level(time),
+            to: level,
+            start: time,
+            duration,
+        };
+    }
+
+    fn apply(&self, simulation: &mut Simulation) {
+        let level = self.ramp.level(simulation.time());
+        if self.action == Action::Clutch {
+            simulation.set_clutch(level);
+        } else {
+            simulation.set_pedal(self.action, level);
+        }
+    }
+}
+
+struct Check<'a> {
+    expectation: &'a Expectation,
+    condition: Condition,
+    outcome: Option<Result<f64, String>>,
+}
+
+impl Check<'_> {
+    fn observe(&mut self, simulation: &Simulation) {
+        if self.outcome.is_some() {
+            return;
+        }
+        let time = simulation.time();
+        let holds = self.condition.holds(&simulation.player);
+
+        if let Some(by) = self.expectation.by {
+            if holds {
+                self.outcome = Some(Ok(time));
+            } else if time >= by {
+                self.outcome = Some(Err(state(simulation)));
+            }
+        } else if let Some(at) = self.expectation.at {
+            if time >= at {
+                self.outcome = Some(if holds {
+                    Ok(time)
+                } else {
+                    Err(state(simulation))
+                });
+            }
+        }
+    }
+
+    fn finish(&mut self, simulation: &Simulation) {
+        if self.outcome.is_none() {
+            let holds = self.expectation.by.is_none()
+                && self.expectation.at.is_none()
+                && self.condition.holds(&simulation.player);
+            self.outcome = Some(if holds {
+                Ok(simulation.time())
+            } else {
+                Err(state(simulation))
+            });
+        }
+    }
+
+    fn description(&self) -> String {
+        let expectation = self.expectation;
+        match (expectation.by, expectation.at) {
+            (Some(by), _) => format!("{} by t={by}", expectation.that),
+            (None, Some(at)) => format!("{} at t={at}", expectation.that),
+            (None, None) => format!("{} at the end", expectation.that),
+        }
+    }
+}
+
+fn load(path: &Path) -> Result<Script, Error> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::simulation(Some(path), format!("unable to read script: {e}")))?;
+    toml::from_str(&content)
+        .map_err(|e| Error::simulation(Some(path), format!("invalid script: {e}")))
+}
+
+fn state(simulation: &Simulation) -> String {
+    let player = &simulation.player;
+    format!(
+        "t={:6.2} rpm={:5.0} kmh={:6.1} gear={}",
+        simulation.time(),
+        player.rpm,
+        player.kmh,
+        player.gear().label()
+    )
+}
+
+fn apply(simulation: &mut Simulation, pedals: &mut [Pedal; 3], step: &Step) {
+    let time = simulation.time();
+    let duration = step.over.unwrap_or(0.0);
+    let levels = [
+        step.clutch.map(|down| f64::from(u8::from(down))),
+        step.throttle,
+        step.brake,
+    ];
+    for (pedal, level) in pedals.iter_mut().zip(levels) {
+        if let Some(level) = level {
+            pedal.set(level.clamp(0.0, 1.0), time, duration);
+        }
+    }
+    if let Some(gear) = step.gear {
+        simulation.driver.target = Some(gear);
+    }
+}
+
+fn run_script(path: &Path) -> Result<(), Error> {
+    let script = load(path)?;
+    let mut checks = script
+        .expectations
+        .iter()
+        .map(|expectation| {
+            Ok(Check {
+                expectation,
+                condition: expectation
+                    .that
+                    .parse()
+                    .map_err(|e| Error::simulation(Some(path), e))?,
+                outcome: None,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let mut simulation = Simulation::new(script.assists.clone());
+    let mut pedals = [Action::Clutch, Action::Accelerate, Action::Brake].map(Pedal::new);
+    let mut steps = script.steps.iter().peekable();
+    let mut previous_step = 0.0;
+    let mut next_trace = 0.0;
+    let mut top_speed: f64 = 0.0;
+
+    println!("{}", path.display());
+    while simulation.time() <= script.duration {
+        while let Some(step) = steps.next_if(|step| step.ready(&simulation, previous_step)) {
+            apply(&mut simulation, &mut pedals, step);
+            previous_step = simulation.time();
+        }
+        for pedal in &pedals {
+            pedal.apply(&mut simulation);
+        }
+
+        if script.trace > 0.0 && simulation.time() >= next_trace {
+            println!("{}", state(&simulation));
+            next_trace += script.trace;
+        }
+
+        simulation.step();
+        top_speed = top_speed.max(simulation.player.kmh);
+        for check in &mut checks {
+            check.observe(&simulation);
+        }
+    }
+
+    let player = &simulation.player;
+    println!(
+        "finished after {:.2}s: gear {}, {:.0} rpm, {:.1} km/h, top speed {top_speed:.1} km/h, {:.3} km driven",
+        simulation.time(),
+        player.gear().label(),
+        player.rpm,
+        player.kmh,
+        player.trip
+    );
+    println!("shifts: {}", player.scoring.summary());
+    println!("times: {}", player.timer.summary());
+    let untriggered = steps.count();
+    if untriggered > 0 {
+        println!("{untriggered} step(s) never triggered");
+    }
+
+    let mut failures = 0;
+    for check in &mut checks {
+        check.finish(&simulation);
+        match &check.outcome {
+            Some(Ok(time)) => println!("pass: {} (t={time:.2})", check.description()),
+            Some(Err(state)) => {
+                failures += 1;
+                println!("FAIL: {} ({state})", check.description());
+            }
+            None => {}
+        }
+    }
+
+    if failures == 0 {
+        Ok(())
+    } else {
+        Err(Error::simulation(
+            Some(path),
+            format!("{failures} of {} expectation(s) failed", checks.len()),
+        ))
+    }
+}
+
+pub fn run(paths: &[PathBuf]) -> Result<(), Error> {
+    let mut failures: Vec<Error> = paths
+        .iter()
+        .filter_map(|path| run_script(path).err())
+        .collect();
+
+    match failures.len() {
+        0 => Ok(()),
+        1 => Err(failures.remove(0)),
+        count => {
+            for failure in &failures {
+                log::error!("{failure}");
+            }
+            Err(Error::simulation(
+                None,
+                format!("{count} of {} scenarios failed", paths.len()),
+            ))
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::assets::Assets;
+use crate::cli::{Cli, PlayArgs};
+use crate::cluster::Cluster;
+use crate::error::Error;
+use crate::frame::Renderer;
+use crate::input::Keys;
+use crate::scene::Scene;
+use crate::storage;
+use crate::telemetry::{Corner, MAX_SECONDS};
+use crate::units::Units;
+
+pub const MIN_SENSITIVITY: f64 = 16.0;
+pub const MAX_SENSITIVITY: f64 = 512.0;
+pub const MAX_VOLUME: u8 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Assists {
+    pub rev_match: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub mouse_sensitivity: f64,
+    pub windowed: bool,
+    pub width: u32,
+    pub height: u32,
+    pub scale: Option<f64>,
+    pub renderer: Renderer,
+    pub vsync: bool,
+    pub max_fps: Option<u32>,
+    pub theme: PathBuf,
+    pub cluster: Cluster,
+    pub units: Units,
+    pub scene: Scene,
+    pub camera_shake: bool,
+    pub pedal_traces: bool,
+    pub telemetry: bool,
+    pub telemetry_corner: Corner,
+    pub telemetry_seconds: u32,
+    pub assists: Assists,
+    pub volume: u8,
+    pub keys: Keys,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            mouse_sensitivity: 128.0,
+            windowed: false,
+            width: 1920,
+            height: 800,
+            scale: None,
+            renderer: Renderer::Accelerated,
+            vsync: false,
+            max_fps: None,
+            theme: PathBuf::from("default"),
+            cluster: Cluster::Analog,
+            units: Units::Metric,
+            scene: Scene::Road,
+            camera_shake: false,
+            pedal_traces: false,
+            telemetry: false,
+            telemetry_corner: Corner::TopRight,
+            telemetry_seconds: 10,
+            assists: Assists::default(),
+            volume: MAX_VOLUME,
+            keys: Keys::default(),
+        }
+    }
+}
+
+pub fn path() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config.join("car-demo").join("settings.toml"))
+}
+
+impl Settings {
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        storage::load(path, "settings")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        storage::save(path, "settings", self)
+    }
+
+    pub fn with_args(self, cli: &PlayArgs) -> Self {
+        Self {
+            mouse_sensitivity: cli.mouse_sensitivity.unwrap_or(self.mouse_sensitivity),
+            windowed: cli.windowed.unwrap_or(self.windowed),
+            width: cli.width.unwrap_or(self.width),
+            height: cli.height.unwrap_or(self.height),
+            scale: cli.scale.or(self.scale),
+            renderer: cli.renderer.unwrap_or(self.renderer),
+            vsync: cli.vsync.unwrap_or(self.vsync),
+            max_fps: cli.max_fps.or(self.max_fps),
+            theme: cli.theme.clone().unwrap_or(self.theme),
+            cluster: cli.cluster.unwrap_or(self.cluster),
+            units: cli.units.unwrap_or(self.units),
+            scene: cli.scene.unwrap_or(self.scene),
+            camera_shake: cli.camera_shake.unwrap_or(self.camera_shake),
+            pedal_traces: cli.pedal_traces.unwrap_or(self.pedal_traces),
+            telemetry: cli.telemetry.unwrap_or(self.telemetry),
+            telemetry_corner: cli.telemetry_corner.unwrap_or(self.telemetry_corner),
+            telemetry_seconds: cli
+                .telemetry_seconds
+                .unwrap_or(self.telemetry_seconds)
+                .clamp(1, MAX_SECONDS),
+            ..self
+        }
+    }
+}
+
+pub struct Profile {
+    pub path: Option<PathBuf>,
+    pub stored: Settings,
+}
+
+impl Profile {
+    pub fn load(cli: &Cli) -> Result<Self, Error> {
+        let path = cli.config.clone().or_else(path);
+        let stored = match &path {
+            Some(path) => Settings::load(path)?.unwrap_or_else(|| {
+                log::info!("no settings at {}, using defaults", path.display());
+                Settings::default()
+            }),
+            None => Settings::default(),
+        };
+
+        Ok(Self { path, stored })
+    }
+
+    pub fn save(
+        &mut self,
+        before: &Settings,
+        after: &Settings,
+        assets: &Assets,
+    ) -> Result<(), Error> {
+        let stored = &mut self.stored;
+        if after.mouse_sensitivity != before.mouse_sensitivity {
+            stored.mouse_sensitivity = after.mouse_sensitivity;
+        }
+        if after.units != before.units {
+            stored.units = after.units;
+        }
+        if after.theme != before.theme {
+            stored.theme = assets.theme_name(&after.theme);
+        }
+        if after.assists != before.assists {
+            stored.assists = after.assists.clone();
+        }
+        if after.volume != before.volume {
+            stored.volume = after.volume;
+        }
+        if after.keys != before.keys {
+            stored.keys = after.keys.clone();
+        }
+
+        match &self.path {
+            Some(path) => self.stored.save(path),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::gear_stick::Gear;
+use crate::input::{Action, Input};
+use crate::player::Player;
+use crate::settings::Assists;
+
+pub const TICK_SECONDS: f64 = 1.0 / 60.0;
+
+const LANE_TOLERANCE: f64 = 0.05;
+const NEUTRAL_BAND: f64 = 0.4;
+const REACH: f64 = 0.1;
+
+fn gate_position(gear: Gear, lane: f64) -> (f64, f64) {
+    match gear {
+        Gear::Neutral => (lane, 0.0),
+        Gear::First => (-1.0, -1.0),
+        Gear::Second => (-1.0, 1.0),
+        Gear::Third => (0.0, -1.0),
+        Gear::Fourth => (0.0, 1.0),
+        Gear::Fifth => (1.0, -1.0),
+        Gear::Rocket => (1.0, 1.0),
+    }
+}
+
+fn press(input: &mut Input, action: Action, down: bool) {
+    if down {
+        input.key_down(action);
+    } else if input.action_active(&action) {
+        input.key_up(action);
+    }
+}
+
+#[derive(Default)]
+pub struct Driver {
+    pub target: Option<Gear>,
+}
+
+impl Driver {
+    fn waypoint(player: &Player, gear: Gear) -> (f64, f64) {
+        let (x, y) = player.gear_stick.offset;
+        let (target_x, target_y) = gate_position(gear, x);
+
+        if (x - target_x).abs() > LANE_TOLERANCE {
+            if y.abs() > NEUTRAL_BAND {
+                (x, 0.0)
+            } else {
+                (target_x, 0.0)
+            }
+        } else {
+            (target_x, target_y)
+        }
+    }
+
+    fn drive(&mut self, player: &mut Player) {
+        let Some(gear) = self.target else {
+            return;
+        };
+
+        if player.gear() == gear && gear != Gear::Neutral {
+            press(&mut player.input, Action::Grab, false);
+            self.target = None;
+            return;
+        }
+
+        if !player.gear_stick.held {
+            let stick = player.gear_stick.offset;
+            player.input.hand = stick;
+            let (hand_x, hand_y) = player.hand.offset;
+            let reached = (hand_x - stick.0).hypot(hand_y - stick.1) < REACH;
+            press(&mut player.input, Action::Grab, reached);
+            return;
+        }
+
+        let waypoint = Self::waypoint(player, gear);
+        player.input.hand = waypoint;
+
+        if gear == Gear::Neutral && player.gear_stick.offset.1.abs() < REACH {
+            press(&mut player.input, Action::Grab, false);
+            self.target = None;
+        }
+    }
+}
+
+pub struct Simulation {
+    pub player: Player,
+    pub driver: Driver,
+    pub assists: Assists,
+    pub ticks: u64,
+}
+
+impl Simulation {
+    pub fn new(assists: Assists) -> Self {
+        Self {
+            player: Player::new(Input::with_sensitivity(128.0)),
+            driver: Driver::default(),
+            assists,
+            ticks: 0,
+        }
+    }
+
+    pub fn time(&self) -> f64 {
+        self.ticks as f64 * TICK_SECONDS
+    }
+
+    pub fn set_pedal(&mut self, action: Action, amount: f64) {
+        let input = &mut self.player.input;
+        match action {
+            Action::Accelerate => input.speeder_alpha = amount.clamp(0.0, 1.0),
+            Action::Brake => input.brake_alpha = amount.clamp(0.0, 1.0),
+            _ => {}
+        }
+        press(input, action, amount > 0.0);
+    }
+
+    pub fn set_clutch(&mut self, amount: f64) {
+        let input = &mut self.player.input;
+        input.clutch_alpha = amount.clamp(0.0, 1.0);
+        press(input, Action::Clutch, amount > 0.0);
+    }
+
+    pub fn step(&mut self) {
+        self.driver.drive(&mut self.player);
+        self.player.move_towards_targets();
+        let gear = self.player.gear();
+        self.player.update(gear, &self.assists);
+        self.ticks += 1;
+    }
+}
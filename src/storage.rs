@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// Reads the TOML file at `path`, or `None` when there is none yet. `what`
+/// names the contents in error messages.
+pub fn load<T: DeserializeOwned>(path: &Path, what: &str) -> Result<Option<T>, Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::config(Some(path), format!("unable to read {what}: {e}")))?;
+    toml::from_str(&content)
+        .map(Some)
+        .map_err(|e| Error::config(Some(path), format!("invalid {what}: {e}")))
+}
+
+/// Writes `value` to `path` as TOML, creating its directory first.
+pub fn save<T: Serialize>(path: &Path, what: &str, value: &T) -> Result<(), Error> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory).map_err(|e| {
+            Error::config(Some(path), format!("unable to create the directory: {e}"))
+        })?;
+    }
+
+    let content = toml::to_string_pretty(value).map_err(|e| Error::config(Some(path), e))?;
+    std::fs::write(path, content)
+        .map_err(|e| Error::config(Some(path), format!("unable to write {what}: {e}")))
+}
@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+
+use clap::ValueEnum;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::atlas::Sprites;
+use crate::drivetrain::MAX_RPM;
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::layout::{Anchor, Layout};
+use crate::player::PedalTravel;
+use crate::simulation::TICK_SECONDS;
+use crate::text::{draw_text, TextStyle};
+use crate::theme::Theme;
+use crate::units::Units;
+
+pub const MAX_SECONDS: u32 = 30;
+
+const WIDTH: i16 = 640;
+const HEIGHT: i16 = 200;
+const LEGEND_HEIGHT: i16 = 24;
+
+const GRID: Color = Color::RGBA(255, 255, 255, 48);
+const SHIFT: Color = Color::RGBA(255, 255, 255, 160);
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Corner {
+    TopLeft,
+    Top,
+    TopRight,
+}
+
+impl Corner {
+    pub fn next(self) -> Self {
+        match self {
+            Corner::TopLeft => Corner::Top,
+            Corner::Top => Corner::TopRight,
+            Corner::TopRight => Corner::TopLeft,
+        }
+    }
+
+    fn position(self, layout: &Layout) -> (i16, i16) {
+        match self {
+            Corner::TopLeft => layout.anchor(&Anchor::TopLeft, (16, 16)),
+            Corner::Top => layout.anchor(&Anchor::Top, (-WIDTH / 2, 16)),
+            Corner::TopRight => layout.anchor(&Anchor::TopRight, (-WIDTH - 16, 16)),
+        }
+    }
+}
+
+pub struct Panel {
+    pub visible: bool,
+    pub corner: Corner,
+    pub seconds: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct Sample {
+    pub rpm: f64,
+    pub kmh: f64,
+    pub pedals: PedalTravel,
+    pub gear: f64,
+    pub shift: bool,
+}
+
+pub struct Telemetry {
+    samples: VecDeque<Sample>,
+}
+
+fn ticks(seconds: u32) -> usize {
+    (f64::from(seconds) / TICK_SECONDS).round() as usize
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(ticks(MAX_SECONDS)),
+        }
+    }
+}
+
+fn gear_level(gear: &Gear) -> f64 {
+    match gear {
+        Gear::Neutral => 0.0,
+        Gear::First => 1.0,
+        Gear::Second => 2.0,
+        Gear::Third => 3.0,
+        Gear::Fourth => 4.0,
+        Gear::Fifth => 5.0,
+        Gear::Rocket => 6.0,
+    }
+}
+
+impl Telemetry {
+    pub fn record(&mut self, rpm: f64, kmh: f64, pedals: PedalTravel, gear: &Gear, shift: bool) {
+        if self.samples.len() == ticks(MAX_SECONDS) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            rpm,
+            kmh,
+            pedals,
+            gear: gear_level(gear),
+            shift,
+        });
+    }
+}
+
+type Series = (&'static str, Color, fn(&Sample, Units) -> f64);
+
+const SERIES: [Series; 6] = [
+    ("rpm", Color::RGB(230, 24, 32), |sample, _| {
+        sample.rpm / MAX_RPM
+    }),
+    ("speed", Color::RGB(64, 160, 255), |sample, units| {
+        units.speed(sample.kmh) / units.speedometer_max()
+    }),
+    ("throttle", Color::RGB(32, 200, 64), |sample, _| {
+        sample.pedals.throttle
+    }),
+    ("brake", Color::RGB(255, 128, 0), |sample, _| {
+        sample.pedals.brake
+    }),
+    ("clutch", Color::RGB(200, 64, 255), |sample, _| {
+        sample.pedals.clutch
+    }),
+    ("gear", Color::RGB(240, 200, 16), |sample, _| {
+        sample.gear / 6.0
+    }),
+];
+
+pub fn panel<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    units: Units,
+    panel: &Panel,
+    telemetry: &Telemetry,
+) -> Result<(), Error> {
+    let (left, top) = panel.corner.position(layout);
+    let plot_top = top + LEGEND_HEIGHT;
+    let bottom = top + HEIGHT;
+    let right = left + WIDTH;
+
+    canvas
+        .box_(left, top, right, bottom, Color::from(theme.hud.panel))
+        .map_err(Error::video)?;
+    for line in 1..4 {
+        let y = bottom - (HEIGHT - LEGEND_HEIGHT) * line / 4;
+        canvas.hline(left, right, y, GRID).map_err(Error::video)?;
+    }
+
+    let ticks = ticks(panel.seconds.min(MAX_SECONDS));
+    let skip = telemetry.samples.len().saturating_sub(ticks);
+    let step = f64::from(WIDTH) / ticks.max(2).saturating_sub(1) as f64;
+    let x = |index: usize| left + (index as f64 * step) as i16;
+    let y =
+        |value: f64| bottom - (value.clamp(0.0, 1.0) * f64::from(HEIGHT - LEGEND_HEIGHT)) as i16;
+
+    let samples: Vec<&Sample> = telemetry.samples.iter().skip(skip).collect();
+    for (index, sample) in samples.iter().enumerate() {
+        if sample.shift {
+            canvas
+                .vline(x(index), plot_top, bottom, SHIFT)
+                .map_err(Error::video)?;
+        }
+    }
+
+    let mut legend = left + 8;
+    for (name, color, value) in SERIES {
+        for (index, pair) in samples.windows(2).enumerate() {
+            canvas
+                .line(
+                    x(index),
+                    y(value(pair[0], units)),
+                    x(index + 1),
+                    y(value(pair[1], units)),
+                    color,
+                )
+                .map_err(Error::video)?;
+        }
+
+        draw_text(
+            canvas,
+            sprites,
+            name,
+            (legend, top + 6),
+            &TextStyle::new(2, color),
+        )?;
+        legend += 104;
+    }
+
+    Ok(())
+}
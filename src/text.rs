@@ -0,0 +1,127 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::error::Error;
+
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+pub struct TextStyle {
+    pub scale: i16,
+    pub color: Color,
+    pub align: Align,
+}
+
+impl TextStyle {
+    pub fn new(scale: i16, color: Color) -> Self {
+        Self {
+            scale,
+            color,
+            align: Align::Left,
+        }
+    }
+
+    pub fn aligned(self, align: Align) -> Self {
+        Self { align, ..self }
+    }
+}
+
+pub fn text_width(sprites: &Sprites, text: &str, style: &TextStyle) -> i32 {
+    let font = &sprites.atlas.font;
+    let characters = i32::try_from(text.chars().count()).unwrap_or(i32::MAX);
+    if characters == 0 {
+        return 0;
+    }
+
+    let advance = i32::try_from(font.advance).unwrap_or(i32::MAX);
+    let glyph_width = i32::try_from(font.size.0).unwrap_or(i32::MAX);
+    let scale = i32::from(style.scale);
+
+    ((characters - 1) * advance + glyph_width).saturating_mul(scale)
+}
+
+fn start_x(sprites: &Sprites, text: &str, position: (i16, i16), style: &TextStyle) -> i32 {
+    match style.align {
+        Align::Left => i32::from(position.0),
+        Align::Center => i32::from(position.0) - text_width(sprites, text, style) / 2,
+        Align::Right => i32::from(position.0) - text_width(sprites, text, style),
+    }
+}
+
+#[cfg(feature = "ttf")]
+fn draw_true_type<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    true_type: &crate::atlas::TrueType,
+    text: &str,
+    position: (i16, i16),
+    style: &TextStyle,
+) -> Result<(), Error> {
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let surface = true_type
+        .font
+        .render(text)
+        .blended(style.color)
+        .map_err(|e| Error::asset(&true_type.path, format!("unable to render `{text}`: {e}")))?;
+    let texture = true_type
+        .texture_creator
+        .create_texture_from_surface(&surface)
+        .map_err(Error::video)?;
+
+    let height = sprites.atlas.font.size.1 * u32::from(style.scale.unsigned_abs());
+    let width = surface.width() * height / surface.height().max(1);
+    let x = match style.align {
+        Align::Left => i32::from(position.0),
+        Align::Center => i32::from(position.0) - i32::try_from(width / 2).map_err(Error::video)?,
+        Align::Right => i32::from(position.0) - i32::try_from(width).map_err(Error::video)?,
+    };
+
+    canvas
+        .copy(
+            &texture,
+            None,
+            Rect::new(x, i32::from(position.1), width, height),
+        )
+        .map_err(Error::video)
+}
+
+pub fn draw_text<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    text: &str,
+    position: (i16, i16),
+    style: &TextStyle,
+) -> Result<(), Error> {
+    #[cfg(feature = "ttf")]
+    if let Some(true_type) = sprites.true_type() {
+        return draw_true_type(canvas, sprites, true_type, text, position, style);
+    }
+
+    let font = &sprites.atlas.font;
+    let scale = u32::from(style.scale.unsigned_abs());
+    let advance = i32::try_from(font.advance * scale).map_err(Error::video)?;
+    let (glyph_width, glyph_height) = (font.size.0 * scale, font.size.1 * scale);
+
+    let mut x = start_x(sprites, text, position, style);
+    for character in text.chars() {
+        if let Some(glyph) = font.glyph(character) {
+            sprites.draw_tinted(
+                canvas,
+                glyph,
+                Rect::new(x, i32::from(position.1), glyph_width, glyph_height),
+                style.color,
+            )?;
+        }
+        x += advance;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+
+use sdl2::pixels::Color;
+use serde::Deserialize;
+
+use crate::error::Error;
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Rgb(u8, u8, u8);
+
+impl From<Rgb> for Color {
+    fn from(Rgb(r, g, b): Rgb) -> Self {
+        Color::RGB(r, g, b)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Rgba(u8, u8, u8, u8);
+
+impl From<Rgba> for Color {
+    fn from(Rgba(r, g, b, a): Rgba) -> Self {
+        Color::RGBA(r, g, b, a)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Colors {
+    pub background: Rgb,
+    pub needle: Rgb,
+    pub needle_shadow: Rgb,
+    pub stick: Rgb,
+    pub text: Rgb,
+    pub dial: Rgb,
+}
+
+#[derive(Deserialize)]
+pub struct Road {
+    pub mountains: Rgb,
+    pub hills: Rgb,
+    pub grass: Rgb,
+    pub asphalt: Rgb,
+    pub markings: Rgb,
+    pub posts: Rgb,
+}
+
+/// Colors of the overlays drawn over the car: panels, the menu, the shift
+/// lights and the good/fair/bad feedback. Themes without a `[hud]` table, or
+/// that leave keys out, get the default ones.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct Hud {
+    pub panel: Rgba,
+    pub menu: Rgba,
+    pub selected: Rgb,
+    pub highlight: Rgb,
+    pub good: Rgb,
+    pub fair: Rgb,
+    pub bad: Rgb,
+    pub stage: Rgb,
+    pub amber: Rgb,
+    pub unlit: Rgb,
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Self {
+            panel: Rgba(0, 0, 0, 160),
+            menu: Rgba(0, 0, 0, 200),
+            selected: Rgb(240, 200, 16),
+            highlight: Rgb(64, 200, 255),
+            good: Rgb(64, 200, 64),
+            fair: Rgb(240, 200, 16),
+            bad: Rgb(220, 48, 48),
+            stage: Rgb(255, 255, 224),
+            amber: Rgb(255, 160, 0),
+            unlit: Rgb(48, 48, 48),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Gauge {
+    pub min_angle: f64,
+    pub max_angle: f64,
+    pub needle_length: f64,
+    pub needle_width: u8,
+    pub shadow_length: f64,
+    pub shadow_width: u8,
+}
+
+#[derive(Deserialize)]
+pub struct Theme {
+    pub colors: Colors,
+    pub tachometer: Gauge,
+    pub speedometer: Gauge,
+    pub road: Road,
+    #[serde(default)]
+    pub hud: Hud,
+}
+
+impl Theme {
+    pub fn load(directory: &Path) -> Result<Self, Error> {
+        let path = directory.join("theme.toml");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| Error::asset(&path, format!("unable to read theme: {e}")))?;
+        Self::parse(&content, &path)
+    }
+
+    pub fn parse(content: &str, path: &Path) -> Result<Self, Error> {
+        toml::from_str(content).map_err(|e| Error::asset(path, format!("invalid theme: {e}")))
+    }
+}
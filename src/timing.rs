@@ -0,0 +1,357 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+use serde::{Deserialize, Serialize};
+
+use crate::atlas::Sprites;
+use crate::drivetrain::IDLE_RPM;
+use crate::error::Error;
+use crate::gear_stick::{expected_kmh, Gear};
+use crate::layout::{Anchor, Layout};
+use crate::simulation::TICK_SECONDS;
+use crate::storage;
+use crate::text::{draw_text, Align, TextStyle};
+use crate::theme::Theme;
+use crate::units::KM_PER_MILE;
+
+const STANDSTILL_MARGIN: f64 = 0.5;
+const RESULT_TICKS: u64 = 180;
+const LINE_HEIGHT: i16 = 24;
+
+#[derive(Clone)]
+pub struct Benchmark {
+    pub name: String,
+    from: f64,
+    to: f64,
+    gear: Option<Gear>,
+}
+
+impl Benchmark {
+    fn new(name: &str, from: f64, to: f64, gear: Option<Gear>) -> Self {
+        Self {
+            name: name.to_string(),
+            from,
+            to,
+            gear,
+        }
+    }
+}
+
+impl FromStr for Benchmark {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (range, gear) = match text.split_once('@') {
+            Some((range, gear)) => (
+                range,
+                Some(Gear::parse(gear).ok_or_else(|| format!("unknown gear `{gear}`"))?),
+            ),
+            None => (text, None),
+        };
+        let (from, to) = range
+            .split_once('-')
+            .ok_or_else(|| format!("`{text}` should look like `60-100` or `80-120@4`"))?;
+        let number = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|e| format!("invalid speed `{value}`: {e}"))
+        };
+        let (from, to) = (number(from)?, number(to)?);
+        if from < 0.0 || to <= from {
+            return Err(format!("`{text}` should go from a lower to a higher speed"));
+        }
+
+        let name = match gear {
+            Some(gear) => format!("{from}-{to} km/h in {}", gear.label()),
+            None => format!("{from}-{to} km/h"),
+        };
+        Ok(Self::new(&name, from, to, gear))
+    }
+}
+
+pub fn benchmarks(extra: &[Benchmark]) -> Vec<Benchmark> {
+    let mut benchmarks = vec![
+        Benchmark::new("0-100 km/h", 0.0, 100.0, None),
+        Benchmark::new("0-60 mph", 0.0, 60.0 * KM_PER_MILE, None),
+        Benchmark::new("100-200 km/h", 100.0, 200.0, None),
+        Benchmark::new("80-120 km/h in 4", 80.0, 120.0, Some(Gear::Fourth)),
+    ];
+    for benchmark in extra {
+        if !benchmarks.iter().any(|other| other.name == benchmark.name) {
+            benchmarks.push(benchmark.clone());
+        }
+    }
+    benchmarks
+}
+
+pub type Times = Vec<Option<f64>>;
+
+#[derive(Clone, Copy, Default)]
+enum Attempt {
+    #[default]
+    Idle,
+    Running(f64),
+}
+
+#[derive(Clone, Copy)]
+struct Finish {
+    index: usize,
+    time: f64,
+    at: u64,
+    improved: bool,
+}
+
+pub struct Timer {
+    benchmarks: Vec<Benchmark>,
+    ticks: u64,
+    kmh: f64,
+    attempts: Vec<Attempt>,
+    best: Times,
+    last: Option<Finish>,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new(benchmarks(&[]))
+    }
+}
+
+impl Timer {
+    pub fn new(benchmarks: Vec<Benchmark>) -> Self {
+        Self {
+            ticks: 0,
+            kmh: 0.0,
+            attempts: vec![Attempt::Idle; benchmarks.len()],
+            best: vec![None; benchmarks.len()],
+            last: None,
+            benchmarks,
+        }
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self {
+            best: std::mem::take(&mut self.best),
+            ..Self::new(std::mem::take(&mut self.benchmarks))
+        };
+    }
+
+    fn seconds(&self) -> f64 {
+        self.ticks as f64 * TICK_SECONDS
+    }
+
+    pub fn record(&mut self, kmh: f64, gear: Gear) {
+        let previous = std::mem::replace(&mut self.kmh, kmh);
+        self.ticks += 1;
+        let now = self.seconds();
+        let crossing = |mark: f64| now - TICK_SECONDS * (kmh - mark) / (kmh - previous);
+        let standstill = expected_kmh(IDLE_RPM, Gear::Neutral.gear_ratio()) + STANDSTILL_MARGIN;
+
+        for index in 0..self.benchmarks.len() {
+            let Benchmark {
+                from,
+                to,
+                gear: wanted,
+                ..
+            } = self.benchmarks[index];
+            let in_gear = wanted.is_none_or(|wanted| wanted == gear);
+            let start = from.max(standstill);
+
+            self.attempts[index] = match self.attempts[index] {
+                Attempt::Running(_) if !in_gear => Attempt::Idle,
+                Attempt::Running(started) if kmh >= to => {
+                    self.finish(index, crossing(to) - started);
+                    Attempt::Idle
+                }
+                Attempt::Running(_) if kmh < start => Attempt::Idle,
+                Attempt::Running(started) => Attempt::Running(started),
+                Attempt::Idle if in_gear && previous <= start && kmh > start => {
+                    Attempt::Running(crossing(start))
+                }
+                Attempt::Idle => Attempt::Idle,
+            };
+        }
+    }
+
+    fn finish(&mut self, index: usize, time: f64) {
+        log::debug!("{} in {time:.2}s", self.benchmarks[index].name);
+        let best = &mut self.best[index];
+        let improved = best.is_none_or(|best| time < best);
+        if improved {
+            *best = Some(time);
+        }
+        self.last = Some(Finish {
+            index,
+            time,
+            at: self.ticks,
+            improved,
+        });
+    }
+
+    pub fn summary(&self) -> String {
+        let times: Vec<String> = self
+            .benchmarks
+            .iter()
+            .zip(&self.best)
+            .filter_map(|(benchmark, best)| Some(format!("{} {:.2}s", benchmark.name, (*best)?)))
+            .collect();
+        if times.is_empty() {
+            "no times".to_string()
+        } else {
+            times.join(", ")
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Records {
+    vehicles: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl Records {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        Ok(storage::load(path, "times")?.unwrap_or_default())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        storage::save(path, "times", self)
+    }
+
+    pub fn best(&self, vehicle: &str, benchmarks: &[Benchmark]) -> Times {
+        let stored = self.vehicles.get(vehicle);
+        benchmarks
+            .iter()
+            .map(|benchmark| stored?.get(&benchmark.name).copied())
+            .collect()
+    }
+
+    pub fn update(&mut self, vehicle: &str, timer: &Timer) -> bool {
+        let stored = self.vehicles.entry(vehicle.to_string()).or_default();
+        let mut improved = false;
+        for (benchmark, time) in timer.benchmarks.iter().zip(&timer.best) {
+            let Some(time) = *time else {
+                continue;
+            };
+            let best = stored.entry(benchmark.name.clone()).or_insert(f64::MAX);
+            if time < *best {
+                *best = time;
+                improved = true;
+            }
+        }
+        improved
+    }
+}
+
+pub fn draw<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    timer: &Timer,
+    stored: &Times,
+) -> Result<(), Error> {
+    let text = Color::from(theme.colors.text);
+    let now = timer.seconds();
+    let mut lines: Vec<(String, Color)> = timer
+        .benchmarks
+        .iter()
+        .zip(&timer.attempts)
+        .filter_map(|(benchmark, attempt)| match attempt {
+            Attempt::Running(started) => {
+                Some((format!("{} {:.2}s", benchmark.name, now - started), text))
+            }
+            Attempt::Idle => None,
+        })
+        .collect();
+
+    if let Some(finish) = timer
+        .last
+        .filter(|finish| timer.ticks - finish.at < RESULT_TICKS)
+    {
+        let Finish { index, time, .. } = finish;
+        let name = &timer.benchmarks[index].name;
+        let stored = stored[index];
+        lines.push(
+            if finish.improved && stored.is_none_or(|stored| time < stored) {
+                (
+                    format!("{name} {time:.2}s, new best"),
+                    theme.hud.good.into(),
+                )
+            } else {
+                let best = stored
+                    .into_iter()
+                    .chain(timer.best[index])
+                    .fold(time, f64::min);
+                (format!("{name} {time:.2}s, best {best:.2}s"), text)
+            },
+        );
+    }
+
+    let (x, y) = layout.anchor(&Anchor::Center, (0, -120));
+    for (row, (line, color)) in (0..).zip(&lines) {
+        draw_text(
+            canvas,
+            sprites,
+            line,
+            (x, y + row * LINE_HEIGHT),
+            &TextStyle::new(2, *color).aligned(Align::Center),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_speed_range() {
+        let benchmark: Benchmark = "60-100".parse().expect("valid benchmark");
+
+        assert_eq!(benchmark.name, "60-100 km/h");
+        assert_eq!((benchmark.from, benchmark.to), (60.0, 100.0));
+        assert_eq!(benchmark.gear, None);
+    }
+
+    #[test]
+    fn parses_a_speed_range_in_a_gear() {
+        let benchmark: Benchmark = "80-120@4".parse().expect("valid benchmark");
+
+        assert_eq!(benchmark.name, "80-120 km/h in 4");
+        assert_eq!(benchmark.gear, Some(Gear::Fourth));
+
+        let benchmark: Benchmark = "30.5-50@second".parse().expect("valid benchmark");
+        assert_eq!(benchmark.from, 30.5);
+        assert_eq!(benchmark.gear, Some(Gear::Second));
+    }
+
+    #[test]
+    fn rejects_malformed_benchmarks() {
+        for text in [
+            "100", "a-100", "60-b", "100-60", "60-60", "-5-60", "60-100@7", "",
+        ] {
+            assert!(
+                text.parse::<Benchmark>().is_err(),
+                "`{text}` should not parse"
+            );
+        }
+    }
+
+    #[test]
+    fn extra_benchmarks_skip_the_built_in_ones() {
+        let extra = [
+            "0-100".parse().expect("valid benchmark"),
+            "50-70".parse().expect("valid benchmark"),
+        ];
+        let names: Vec<String> = benchmarks(&extra)
+            .into_iter()
+            .map(|benchmark| benchmark.name)
+            .collect();
+
+        assert_eq!(names.iter().filter(|name| *name == "0-100 km/h").count(), 1);
+        assert_eq!(names.last().map(String::as_str), Some("50-70 km/h"));
+    }
+}
@@ -0,0 +1,290 @@
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
+use sdl2::render::{Canvas, RenderTarget};
+
+use crate::atlas::Sprites;
+use crate::debug::{gear_region, point};
+use crate::draw::{gearstick_position, pedal_position, Pedal, PEDAL_SIZE, STICK_TRAVEL};
+use crate::error::Error;
+use crate::gear_stick::Gear;
+use crate::input::Action;
+use crate::layout::{Anchor, Layout};
+use crate::player::Player;
+use crate::text::{draw_text, text_width, Align, TextStyle};
+use crate::theme::Theme;
+use crate::units::Units;
+
+const BITE_RPM: (f64, f64) = (1500.0, 3000.0);
+const BITE_TICKS: u32 = 60;
+const PULL_AWAY_KMH: f64 = 15.0;
+const SHIFT_RPM: (f64, f64) = (4500.0, 6500.0);
+const BRAKE_KMH: f64 = 30.0;
+const STEPS: usize = 8;
+
+const PROMPT_PADDING: i16 = 16;
+const BLINK_TICKS: u64 = 40;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Step {
+    PressClutch,
+    GrabStick,
+    FirstGear,
+    BitePoint,
+    PullAway,
+    ShiftToSecond,
+    Brake,
+    Downshift,
+    Done,
+}
+
+enum Highlight {
+    Pedal(Pedal),
+    Stick,
+    Gear(Gear),
+}
+
+impl Step {
+    fn next(self) -> Self {
+        match self {
+            Step::PressClutch => Step::GrabStick,
+            Step::GrabStick => Step::FirstGear,
+            Step::FirstGear => Step::BitePoint,
+            Step::BitePoint => Step::PullAway,
+            Step::PullAway => Step::ShiftToSecond,
+            Step::ShiftToSecond => Step::Brake,
+            Step::Brake => Step::Downshift,
+            Step::Downshift | Step::Done => Step::Done,
+        }
+    }
+
+    fn prompt(self, units: Units) -> String {
+        match self {
+            Step::PressClutch => "press and hold the clutch".to_string(),
+            Step::GrabStick => "grab the gear stick".to_string(),
+            Step::FirstGear => "keep the clutch down and move the stick into first".to_string(),
+            Step::BitePoint => format!(
+                "find the bite point: hold {:.0}-{:.0} rpm with the clutch down",
+                BITE_RPM.0, BITE_RPM.1
+            ),
+            Step::PullAway => "release the clutch while adding throttle".to_string(),
+            Step::ShiftToSecond => format!(
+                "shift to second between {:.0} and {:.0} rpm",
+                SHIFT_RPM.0, SHIFT_RPM.1
+            ),
+            Step::Brake => format!(
+                "brake below {:.0} {}",
+                units.speed(BRAKE_KMH),
+                units.speed_label()
+            ),
+            Step::Downshift => "clutch in and shift down to first".to_string(),
+            Step::Done => "tutorial complete, drive on".to_string(),
+        }
+    }
+
+    fn highlights(self) -> &'static [Highlight] {
+        match self {
+            Step::PressClutch => &[Highlight::Pedal(Pedal::Clutch)],
+            Step::GrabStick => &[Highlight::Stick],
+            Step::FirstGear => &[Highlight::Gear(Gear::First)],
+            Step::BitePoint => &[
+                Highlight::Pedal(Pedal::Clutch),
+                Highlight::Pedal(Pedal::Throttle),
+            ],
+            Step::PullAway => &[
+                Highlight::Pedal(Pedal::Clutch),
+                Highlight::Pedal(Pedal::Throttle),
+            ],
+            Step::ShiftToSecond => &[
+                Highlight::Pedal(Pedal::Clutch),
+                Highlight::Gear(Gear::Second),
+            ],
+            Step::Brake => &[Highlight::Pedal(Pedal::Brake)],
+            Step::Downshift => &[
+                Highlight::Pedal(Pedal::Clutch),
+                Highlight::Gear(Gear::First),
+            ],
+            Step::Done => &[],
+        }
+    }
+}
+
+pub struct Tutorial {
+    step: Step,
+    ticks: u64,
+    held: u32,
+    shift_rpm: Option<f64>,
+    feedback: Option<String>,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            step: Step::PressClutch,
+            ticks: 0,
+            held: 0,
+            shift_rpm: None,
+            feedback: None,
+        }
+    }
+}
+
+impl Tutorial {
+    pub fn update(&mut self, player: &Player) {
+        self.ticks += 1;
+
+        let clutch = player.input.action_active(&Action::Clutch);
+        let throttle = player.input.action_active(&Action::Accelerate);
+        let stick = player.gear_stick.gear(false);
+        let gear = player.gear();
+
+        let done = match self.step {
+            Step::PressClutch => clutch && player.pedals.clutch >= 1.0,
+            Step::GrabStick => player.gear_stick.held,
+            Step::FirstGear => clutch && stick == Gear::First,
+            Step::BitePoint => {
+                let revving = (BITE_RPM.0..=BITE_RPM.1).contains(&player.rpm);
+                self.held = if clutch && stick == Gear::First && revving {
+                    self.held + 1
+                } else {
+                    0
+                };
+                self.held >= BITE_TICKS
+            }
+            Step::PullAway => gear == Gear::First && throttle && player.kmh >= PULL_AWAY_KMH,
+            Step::ShiftToSecond => {
+                if gear == Gear::First {
+                    self.shift_rpm = Some(player.rpm);
+                }
+                match (gear, self.shift_rpm) {
+                    (Gear::Second, Some(rpm)) if (SHIFT_RPM.0..=SHIFT_RPM.1).contains(&rpm) => true,
+                    (Gear::Second, Some(rpm)) => {
+                        self.feedback = Some(format!(
+                            "shifted at {rpm:.0} rpm, go back to first and try again"
+                        ));
+                        self.shift_rpm = None;
+                        false
+                    }
+                    _ => false,
+                }
+            }
+            Step::Brake => player.input.action_active(&Action::Brake) && player.kmh < BRAKE_KMH,
+            Step::Downshift => gear == Gear::First,
+            Step::Done => false,
+        };
+
+        if done {
+            self.step = self.step.next();
+            self.held = 0;
+            self.shift_rpm = None;
+            self.feedback = None;
+            log::debug!("tutorial step {}", self.number());
+        }
+    }
+
+    fn number(&self) -> usize {
+        self.step as usize + 1
+    }
+}
+
+fn highlight<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    layout: &Layout,
+    player: &Player,
+    highlight: &Highlight,
+    color: Color,
+) -> Result<(), Error> {
+    let ((x1, y1), (x2, y2)) = match highlight {
+        Highlight::Pedal(pedal) => {
+            let (x, y) = pedal_position(layout, *pedal);
+            ((x, y), (x + PEDAL_SIZE, y + PEDAL_SIZE))
+        }
+        Highlight::Stick => {
+            let (x, y) = point(gearstick_position(layout), player.gear_stick.offset);
+            let radius = (STICK_TRAVEL * 0.6) as i16;
+            for width in 0..4 {
+                canvas
+                    .circle(x, y, radius + width, color)
+                    .map_err(Error::video)?;
+            }
+            return Ok(());
+        }
+        Highlight::Gear(gear) => {
+            let Some((from, to)) = gear_region(*gear) else {
+                return Ok(());
+            };
+            let origin = gearstick_position(layout);
+            (point(origin, from), point(origin, to))
+        }
+    };
+
+    for width in 0..4 {
+        canvas
+            .rectangle(x1 - width, y1 - width, x2 + width, y2 + width, color)
+            .map_err(Error::video)?;
+    }
+    Ok(())
+}
+
+pub fn draw<T: RenderTarget>(
+    canvas: &mut Canvas<T>,
+    sprites: &Sprites,
+    theme: &Theme,
+    layout: &Layout,
+    units: Units,
+    player: &Player,
+    tutorial: &Tutorial,
+) -> Result<(), Error> {
+    let highlight_color = Color::from(theme.hud.highlight);
+    let step = tutorial.step;
+    let title = if step == Step::Done {
+        "tutorial".to_string()
+    } else {
+        format!("step {}/{STEPS}", tutorial.number())
+    };
+    let prompt = step.prompt(units);
+    let feedback = tutorial.feedback.as_deref().unwrap_or_default();
+
+    let title_style = TextStyle::new(3, highlight_color).aligned(Align::Center);
+    let prompt_style = TextStyle::new(3, theme.colors.text.into()).aligned(Align::Center);
+    let feedback_style = TextStyle::new(2, theme.hud.fair.into()).aligned(Align::Center);
+
+    let width = [
+        text_width(sprites, &title, &title_style),
+        text_width(sprites, &prompt, &prompt_style),
+        text_width(sprites, feedback, &feedback_style),
+    ]
+    .into_iter()
+    .max()
+    .unwrap_or_default();
+    let half = i16::try_from(width / 2).unwrap_or(i16::MAX / 2) + PROMPT_PADDING;
+    let (x, y) = layout.anchor(&Anchor::Center, (0, -400));
+    let height = if feedback.is_empty() { 104 } else { 136 };
+
+    canvas
+        .box_(
+            x - half,
+            y,
+            x + half,
+            y + height,
+            Color::from(theme.hud.panel),
+        )
+        .map_err(Error::video)?;
+    draw_text(
+        canvas,
+        sprites,
+        &title,
+        (x, y + PROMPT_PADDING),
+        &title_style,
+    )?;
+    draw_text(canvas, sprites, &prompt, (x, y + 56), &prompt_style)?;
+    if !feedback.is_empty() {
+        draw_text(canvas, sprites, feedback, (x, y + 100), &feedback_style)?;
+    }
+
+    if tutorial.ticks % BLINK_TICKS < BLINK_TICKS / 2 {
+        for target in step.highlights() {
+            highlight(canvas, layout, player, target, highlight_color)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+pub const KM_PER_MILE: f64 = 1.609_344;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn distance(self, km: f64) -> f64 {
+        match self {
+            Units::Metric => km,
+            Units::Imperial => km / KM_PER_MILE,
+        }
+    }
+
+    pub fn speed(self, kmh: f64) -> f64 {
+        self.distance(kmh)
+    }
+
+    pub fn distance_label(self) -> &'static str {
+        match self {
+            Units::Metric => "KM",
+            Units::Imperial => "MI",
+        }
+    }
+
+    pub fn speed_label(self) -> &'static str {
+        match self {
+            Units::Metric => "KM/H",
+            Units::Imperial => "MPH",
+        }
+    }
+
+    pub fn speedometer_max(self) -> f64 {
+        match self {
+            Units::Metric => 260.0,
+            Units::Imperial => 160.0,
+        }
+    }
+}
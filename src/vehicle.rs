@@ -0,0 +1,289 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::error::Error;
+use crate::hand::CROSS_GATE;
+
+pub const BUILT_IN: &str = "default";
+
+#[derive(Deserialize)]
+struct Gate {
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+#[derive(Deserialize)]
+struct GearDefinition {
+    name: String,
+    ratio: f64,
+    #[serde(default)]
+    reverse: bool,
+    gate: Gate,
+}
+
+#[derive(Deserialize)]
+pub struct Vehicle {
+    pub name: String,
+    idle_rpm: f64,
+    redline_rpm: f64,
+    max_rpm: f64,
+    final_drive: f64,
+    tire_diameter: f64,
+    #[serde(rename = "gear")]
+    gears: Vec<GearDefinition>,
+}
+
+fn positive(value: f64) -> bool {
+    value.is_finite() && value > 0.0
+}
+
+impl Gate {
+    fn problems(&self, name: &str) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (axis, [min, max]) in [("x", self.x), ("y", self.y)] {
+            if min.partial_cmp(&max) != Some(Ordering::Less) {
+                problems.push(format!(
+                    "gear `{name}`: gate {axis} range {min}..{max} is empty"
+                ));
+            }
+            if min < -1.0 || max > 1.0 {
+                problems.push(format!(
+                    "gear `{name}`: gate {axis} range {min}..{max} is outside the shifter (-1..1)"
+                ));
+            }
+        }
+        if self.y[0] < CROSS_GATE && self.y[1] > -CROSS_GATE {
+            problems.push(format!(
+                "gear `{name}`: gate overlaps the neutral cross-gate (-{CROSS_GATE}..{CROSS_GATE})"
+            ));
+        }
+        problems
+    }
+
+    fn overlaps(&self, other: &Gate) -> bool {
+        self.x[0] < other.x[1]
+            && other.x[0] < self.x[1]
+            && self.y[0] < other.y[1]
+            && other.y[0] < self.y[1]
+    }
+}
+
+impl Vehicle {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| Error::simulation(Some(path), format!("unable to read vehicle: {e}")))?;
+        toml::from_str(&content)
+            .map_err(|e| Error::simulation(Some(path), format!("invalid vehicle: {e}")))
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !positive(self.idle_rpm) {
+            problems.push(format!("idle rpm {} must be positive", self.idle_rpm));
+        }
+        if self.idle_rpm.partial_cmp(&self.redline_rpm) != Some(Ordering::Less) {
+            problems.push(format!(
+                "redline {} rpm must be above idle {} rpm",
+                self.redline_rpm, self.idle_rpm
+            ));
+        }
+        if !matches!(
+            self.redline_rpm.partial_cmp(&self.max_rpm),
+            Some(Ordering::Less | Ordering::Equal)
+        ) {
+            problems.push(format!(
+                "max rpm {} must not be below the redline {} rpm",
+                self.max_rpm, self.redline_rpm
+            ));
+        }
+        if !positive(self.final_drive) {
+            problems.push(format!("final drive {} must be positive", self.final_drive));
+        }
+        if !positive(self.tire_diameter) {
+            problems.push(format!(
+                "tire diameter {} must be positive",
+                self.tire_diameter
+            ));
+        }
+        if self.gears.iter().all(|gear| gear.reverse) {
+            problems.push("no forward gears".to_string());
+        }
+
+        let mut previous: Option<&GearDefinition> = None;
+        for (index, gear) in self.gears.iter().enumerate() {
+            if self.gears[..index]
+                .iter()
+                .any(|other| other.name == gear.name)
+            {
+                problems.push(format!("gear `{}` is defined twice", gear.name));
+            }
+            if !positive(gear.ratio) {
+                problems.push(format!(
+                    "gear `{}`: ratio {} must be positive",
+                    gear.name, gear.ratio
+                ));
+            }
+            if !gear.reverse {
+                if let Some(lower) = previous.filter(|lower| gear.ratio >= lower.ratio) {
+                    problems.push(format!(
+                        "gear `{}`: ratio {} must be lower than `{}` ({})",
+                        gear.name, gear.ratio, lower.name, lower.ratio
+                    ));
+                }
+                previous = Some(gear);
+            }
+
+            problems.extend(gear.gate.problems(&gear.name));
+            for other in &self.gears[..index] {
+                if gear.gate.overlaps(&other.gate) {
+                    problems.push(format!(
+                        "gates of `{}` and `{}` overlap",
+                        other.name, gear.name
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+pub fn validate(path: &Path) -> Result<(), Error> {
+    let vehicle = Vehicle::load(path)?;
+    let problems = vehicle.problems();
+
+    if problems.is_empty() {
+        println!("{}: `{}` is valid", path.display(), vehicle.name);
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("{}: {problem}", path.display());
+    }
+    Err(Error::simulation(
+        Some(path),
+        format!("{} problem(s) in the vehicle", problems.len()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT: &str = include_str!("../assets/vehicles/default.toml");
+
+    const ENGINE: &str = r#"
+name = "test"
+idle_rpm = 700.0
+redline_rpm = 7000.0
+max_rpm = 8000.0
+final_drive = 3.23
+tire_diameter = 26.5
+"#;
+
+    fn vehicle(gears: &str) -> Vehicle {
+        toml::from_str(&format!("{ENGINE}{gears}")).expect("valid vehicle toml")
+    }
+
+    #[test]
+    fn the_bundled_vehicle_has_no_problems() {
+        let vehicle: Vehicle = toml::from_str(DEFAULT).expect("valid vehicle toml");
+
+        assert_eq!(vehicle.problems(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_overlapping_gates() {
+        let vehicle = vehicle(
+            r#"
+[[gear]]
+name = "first"
+ratio = 3.55
+gate = { x = [-1.0, -0.5], y = [-1.0, -0.9] }
+
+[[gear]]
+name = "second"
+ratio = 1.92
+gate = { x = [-0.75, 0.0], y = [-0.95, -0.85] }
+"#,
+        );
+
+        assert_eq!(
+            vehicle.problems(),
+            vec!["gates of `first` and `second` overlap".to_string()]
+        );
+    }
+
+    #[test]
+    fn reports_forward_ratios_out_of_order() {
+        let vehicle = vehicle(
+            r#"
+[[gear]]
+name = "first"
+ratio = 1.92
+gate = { x = [-1.0, -0.9], y = [-1.0, -0.9] }
+
+[[gear]]
+name = "second"
+ratio = 3.55
+gate = { x = [-1.0, -0.9], y = [0.9, 1.0] }
+"#,
+        );
+
+        assert_eq!(
+            vehicle.problems(),
+            vec!["gear `second`: ratio 3.55 must be lower than `first` (1.92)".to_string()]
+        );
+    }
+
+    #[test]
+    fn a_reverse_gear_keeps_its_own_ratio() {
+        let vehicle = vehicle(
+            r#"
+[[gear]]
+name = "first"
+ratio = 3.55
+gate = { x = [-1.0, -0.9], y = [-1.0, -0.9] }
+
+[[gear]]
+name = "rocket"
+ratio = 3.58
+reverse = true
+gate = { x = [0.9, 1.0], y = [0.9, 1.0] }
+
+[[gear]]
+name = "second"
+ratio = 1.92
+gate = { x = [-1.0, -0.9], y = [0.9, 1.0] }
+"#,
+        );
+
+        assert!(vehicle.problems().is_empty());
+    }
+
+    #[test]
+    fn reports_gates_in_the_cross_gate_and_duplicate_names() {
+        let vehicle = vehicle(
+            r#"
+[[gear]]
+name = "first"
+ratio = 3.55
+gate = { x = [-1.0, -0.9], y = [-0.5, 0.5] }
+
+[[gear]]
+name = "first"
+ratio = 1.92
+gate = { x = [0.9, 1.0], y = [0.9, 1.0] }
+"#,
+        );
+        let problems = vehicle.problems();
+
+        assert!(problems.iter().any(
+            |problem| problem.starts_with("gear `first`: gate overlaps the neutral cross-gate")
+        ));
+        assert!(problems.contains(&"gear `first` is defined twice".to_string()));
+    }
+}
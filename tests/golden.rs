@@ -0,0 +1,21 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+#[ignore = "needs the references in golden/, blessed with `car-demo golden --bless` on a machine with sdl2"]
+fn dashboard_matches_golden_images() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let output = Command::new(env!("CARGO_BIN_EXE_car-demo"))
+        .current_dir(root)
+        .args(["golden", "--directory"])
+        .arg(root.join("golden"))
+        .output()
+        .expect("unable to run car-demo");
+
+    assert!(
+        output.status.success(),
+        "headless rendering failed with {}:\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
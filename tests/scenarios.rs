@@ -0,0 +1,32 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn bundled_scenarios_pass() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let mut scripts: Vec<_> = std::fs::read_dir(root.join("assets/scripts"))
+        .expect("unable to list the bundled scenarios")
+        .map(|entry| entry.expect("unable to read the scenario directory").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .collect();
+    scripts.sort();
+    assert!(!scripts.is_empty(), "no scenarios in assets/scripts");
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_car-demo"));
+    command.arg("simulate");
+    for script in &scripts {
+        command.arg("--script").arg(script);
+    }
+    let output = command.output().expect("unable to run car-demo");
+
+    assert!(
+        output.status.success(),
+        "scenarios failed with {}:\n{}\n{}",
+        output.status,
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}